@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AccurateRipRecord } from "./AccurateRipRecord";
+
+export interface AccurateRipPressing { disc_id1: number, disc_id2: number, cddb: number, tracks: Array<AccurateRipRecord>, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CueMismatch } from "./CueMismatch";
+
+export interface CueComparison { mismatches: Array<CueMismatch>, unchecked: string | null, }
\ No newline at end of file
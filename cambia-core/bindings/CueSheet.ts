@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CueFile } from "./CueFile";
+import type { CueRem } from "./CueRem";
+import type { CueTrack } from "./CueTrack";
+
+export interface CueSheet { catalog: string | null, title: string | null, performer: string | null, rem: Array<CueRem>, files: Array<CueFile>, tracks: Array<CueTrack>, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { EvaluationUnitData } from "./EvaluationUnitData";
+import type { Score } from "./Score";
+
+export interface EvaluationUnit { unit_score: Score, data: EvaluationUnitData, }
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type EvaluationUnitField = "Encoding" | "RipperVersion" | "Drive" | "Ripper" | "Offset" | "Cache" | "TestAndCopy" | "Encoder" | "Checksum" | "MediaType" | "ReadMode" | "MaxRetryCount" | "AccurateStream" | "C2" | "SilentSamples" | "NullSamples" | "Gap" | "Tag" | "Gain" | "RangeSplit" | "Samples" | "SilentBlocks" | "Normalization" | "Filename" | "ReadError" | "SkipError" | "JitterGenericError" | "JitterEdgeError" | "JitterAtomError" | "DriftError" | "DroppedError" | "DuplicatedError" | "InconsistentErrorSectors" | "DamagedSector" | "Abort" | "Anomaly";
\ No newline at end of file
@@ -0,0 +1,3 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+
+export type Gap = "Append" | "Append (except HTOA)" | "Append (gaps undetected)" | "Prepend" | "Discard" | "Unknown" | "Inapplicable";
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { ParsedLog } from "./ParsedLog";
+
+export interface ParsedLogCombined { parsed_logs: Array<ParsedLog>, encoding: string, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TrackErrorData } from "./TrackErrorData";
+
+export interface TrackError { read: TrackErrorData, skip: TrackErrorData, "Jitter (maybe fixed)": TrackErrorData, "Edge jitter (maybe fixed)": TrackErrorData, "Atom jitter (maybe fixed)": TrackErrorData, drift: TrackErrorData, "Dropped bytes": TrackErrorData, "Duplicated bytes": TrackErrorData, "Retry sectors": TrackErrorData, "Damaged sectors": TrackErrorData, "Inconsistency in error sectors": TrackErrorData, "Missing samples": TrackErrorData, }
\ No newline at end of file
@@ -0,0 +1,141 @@
+use serde::{Serialize, Deserialize};
+use ts_rs::TS;
+
+use crate::{error::CambiaError, parser::ParsedLog, track::AccurateRipStatus};
+
+// Disc count, the three disc IDs
+static HEADER_LEN: usize = 13;
+// Confidence, CRC, CRC of frame 450 used for offset detection
+static TRACK_LEN: usize = 9;
+
+// One pressing of the disc as stored in a dBAR file
+// The file doesn't say whether a CRC is v1 or v2, both versions are stored side by side as separate pressings
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct AccurateRipPressing {
+    pub disc_id1: u32,
+    pub disc_id2: u32,
+    pub cddb: u32,
+    pub tracks: Vec<AccurateRipRecord>,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct AccurateRipRecord {
+    pub confidence: u8,
+    // Same format as the signatures in logs
+    pub crc: String,
+    pub frame450_crc: String,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct AccurateRipTrackRecords {
+    pub num: u8,
+    pub records: Vec<AccurateRipRecord>,
+}
+
+#[derive(Serialize, Deserialize, TS, PartialEq)]
+#[ts(export)]
+pub struct AccurateRipTrackComparison {
+    pub num: u8,
+    pub version: Option<u8>,
+    pub sign: String,
+    // Confidence of the records with the same CRC, out of every record of the track
+    pub matching: u32,
+    pub total: u32,
+    pub status: AccurateRipStatus,
+}
+
+impl AccurateRipRecord {
+    fn new(confidence: u8, crc: u32, frame450_crc: u32) -> Self {
+        AccurateRipRecord {
+            confidence,
+            crc: format!("{:08X}", crc),
+            frame450_crc: format!("{:08X}", frame450_crc),
+        }
+    }
+}
+
+// Decodes a dBAR-NNN-xxxxxxxx-xxxxxxxx-xxxxxxxx.bin response, little endian throughout
+pub fn decode(data: &[u8]) -> Result<Vec<AccurateRipPressing>, CambiaError> {
+    let mut pressings: Vec<AccurateRipPressing> = Vec::new();
+    let mut pos: usize = 0;
+
+    while pos < data.len() {
+        let header = data.get(pos..pos + HEADER_LEN).ok_or_else(|| CambiaError::new_anon("Truncated AccurateRip response header"))?;
+        let track_count = header[0] as usize;
+        pos += HEADER_LEN;
+
+        let body = data.get(pos..pos + track_count * TRACK_LEN).ok_or_else(|| CambiaError::new_anon("Truncated AccurateRip response, fewer tracks than the header says"))?;
+        pos += track_count * TRACK_LEN;
+
+        pressings.push(AccurateRipPressing {
+            disc_id1: read_u32(&header[1..5]),
+            disc_id2: read_u32(&header[5..9]),
+            cddb: read_u32(&header[9..13]),
+            tracks: body.chunks_exact(TRACK_LEN)
+                .map(|track| AccurateRipRecord::new(track[0], read_u32(&track[1..5]), read_u32(&track[5..9])))
+                .collect(),
+        });
+    }
+
+    Ok(pressings)
+}
+
+// Records per track across every pressing, tracks are numbered from 1 in order of the audio tracks
+pub fn track_records(pressings: &[AccurateRipPressing]) -> Vec<AccurateRipTrackRecords> {
+    let track_count = pressings.iter().map(|pressing| pressing.tracks.len()).max().unwrap_or_default();
+
+    (0..track_count)
+        .map(|idx| AccurateRipTrackRecords {
+            num: (idx + 1) as u8,
+            records: pressings.iter().filter_map(|pressing| pressing.tracks.get(idx)).cloned().collect(),
+        })
+        .collect()
+}
+
+// Checks every signature in the log against the records of its track
+// Records are indexed by audio track, so mixed mode discs are mapped through the TOC
+pub fn compare(pressings: &[AccurateRipPressing], parsed_log: &ParsedLog) -> Vec<AccurateRipTrackComparison> {
+    let audio_entries = parsed_log.toc.raw.audio_entries();
+    let records = track_records(pressings);
+
+    parsed_log.tracks.iter()
+        .flat_map(|track| {
+            let track_records = audio_entries.iter()
+                .position(|entry| entry.track == u32::from(track.num))
+                .and_then(|idx| records.get(idx));
+
+            track.ar_info.iter()
+                .filter(|unit| !unit.sign.is_empty())
+                .map(move |unit| {
+                    let (matching, total, status) = match track_records {
+                        Some(track_records) if !track_records.records.is_empty() => {
+                            let matching: u32 = track_records.records.iter()
+                                .filter(|record| record.crc.eq_ignore_ascii_case(&unit.sign))
+                                .map(|record| u32::from(record.confidence))
+                                .sum();
+                            let total: u32 = track_records.records.iter().map(|record| u32::from(record.confidence)).sum();
+                            let status = if matching > 0 { AccurateRipStatus::Match } else { AccurateRipStatus::Mismatch };
+                            (matching, total, status)
+                        },
+                        _ => (0, 0, AccurateRipStatus::NotFound),
+                    };
+
+                    AccurateRipTrackComparison {
+                        num: track.num,
+                        version: unit.version,
+                        sign: unit.sign.to_uppercase(),
+                        matching,
+                        total,
+                        status,
+                    }
+                })
+        })
+        .collect()
+}
+
+fn read_u32(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
@@ -0,0 +1,135 @@
+use std::collections::HashMap;
+
+use serde::{Serialize, Deserialize};
+use ts_rs::TS;
+
+use crate::{parser::ParsedLog, track::AccurateRipStatus};
+
+// Leeway for TOC times since some rippers write frames where a fraction of a second is expected
+static TOC_TIME_TOLERANCE: f64 = 1.0;
+// Silent tracks legitimately share this hash
+static SILENT_CRC: &str = "00000000";
+
+#[derive(Serialize, Deserialize, TS, Clone, Copy, PartialEq, Eq, Hash, Debug)]
+#[ts(export)]
+pub enum AnomalyKind {
+    TocMismatch,
+    TrackNotInToc,
+    DuplicateCopyCrc,
+    AccurateRipTrackNotInToc,
+    ConflictingSettings,
+    RipperReportedErrors,
+    Truncated,
+    NoTracks,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct Anomaly {
+    pub kind: AnomalyKind,
+    pub track: Option<u8>,
+    pub message: String,
+}
+
+impl Anomaly {
+    pub fn new(kind: AnomalyKind, track: Option<u8>, message: String) -> Self {
+        Anomaly { kind, track, message }
+    }
+
+    pub fn new_release(kind: AnomalyKind, message: String) -> Self {
+        Anomaly::new(kind, None, message)
+    }
+}
+
+// Internal contradictions a parser can't see by itself, run after everything else was extracted
+pub fn check_anomalies(parsed_log: &ParsedLog) -> Vec<Anomaly> {
+    let mut anomalies: Vec<Anomaly> = Vec::new();
+
+    anomalies.append(&mut check_no_tracks(parsed_log));
+    anomalies.append(&mut check_toc(parsed_log));
+    anomalies.append(&mut check_tracks_in_toc(parsed_log));
+    anomalies.append(&mut check_duplicate_crcs(parsed_log));
+
+    anomalies
+}
+
+// A segment of an upload that didn't rip anything, like a fre:ac file conversion
+fn check_no_tracks(parsed_log: &ParsedLog) -> Vec<Anomaly> {
+    if !parsed_log.tracks.is_empty() {
+        return Vec::new();
+    }
+
+    vec![Anomaly::new_release(AnomalyKind::NoTracks, String::from("The log has no ripped tracks"))]
+}
+
+fn check_toc(parsed_log: &ParsedLog) -> Vec<Anomaly> {
+    parsed_log.toc.raw.entries.iter()
+        .filter_map(|entry| {
+            let span = f64::from(entry.end_sector.saturating_sub(entry.start_sector) + 1) / 75.0;
+            let start = f64::from(entry.start_sector) / 75.0;
+
+            let length_off = (entry.length.as_secs_f64() - span).abs() > TOC_TIME_TOLERANCE;
+            let start_off = (entry.start.as_secs_f64() - start).abs() > TOC_TIME_TOLERANCE;
+
+            (length_off || start_off).then(|| Anomaly::new(
+                AnomalyKind::TocMismatch,
+                u8::try_from(entry.track).ok(),
+                format!("TOC times of track {} don't match its sectors", entry.track),
+            ))
+        })
+        .collect()
+}
+
+fn check_tracks_in_toc(parsed_log: &ParsedLog) -> Vec<Anomaly> {
+    let entries = &parsed_log.toc.raw.entries;
+    if entries.is_empty() {
+        return Vec::new();
+    }
+
+    parsed_log.tracks.iter()
+        .filter(|track| !track.is_range && track.num != 0 && !entries.iter().any(|entry| entry.track == u32::from(track.num)))
+        .map(|track| {
+            let has_ar = track.ar_info.iter().any(|ar| matches!(ar.status, AccurateRipStatus::Match | AccurateRipStatus::Mismatch | AccurateRipStatus::Offsetted));
+            match has_ar {
+                true => Anomaly::new(
+                    AnomalyKind::AccurateRipTrackNotInToc,
+                    Some(track.num),
+                    format!("Track {} has AccurateRip results but is not in the TOC", track.num),
+                ),
+                false => Anomaly::new(
+                    AnomalyKind::TrackNotInToc,
+                    Some(track.num),
+                    format!("Track {} is not in the TOC", track.num),
+                ),
+            }
+        })
+        .collect()
+}
+
+fn check_duplicate_crcs(parsed_log: &ParsedLog) -> Vec<Anomaly> {
+    let mut crc_tracks: HashMap<&str, Vec<u8>> = HashMap::new();
+
+    for track in parsed_log.tracks.iter().filter(|track| !track.is_range) {
+        let crc = track.test_and_copy.copy_hash.as_str();
+        if !crc.is_empty() && crc != SILENT_CRC {
+            crc_tracks.entry(crc).or_default().push(track.num);
+        }
+    }
+
+    let mut anomalies: Vec<Anomaly> = crc_tracks.into_iter()
+        .filter(|(_, nums)| nums.len() > 1)
+        .flat_map(|(crc, nums)| {
+            let listed = nums.iter().map(|num| num.to_string()).collect::<Vec<String>>().join(", ");
+            nums.iter()
+                .map(|num| Anomaly::new(
+                    AnomalyKind::DuplicateCopyCrc,
+                    Some(*num),
+                    format!("Copy CRC {} is shared by tracks {}", crc, listed),
+                ))
+                .collect::<Vec<Anomaly>>()
+        })
+        .collect();
+    anomalies.sort_by_key(|anomaly| anomaly.track);
+
+    anomalies
+}
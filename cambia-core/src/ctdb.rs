@@ -0,0 +1,163 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Serialize, Deserialize};
+use ts_rs::TS;
+
+lazy_static! {
+    static ref CTDB_TOCID: Regex = Regex::new(r"\[CTDB TOCID: (?P<tocid>[^\]]+)\] (?P<result>.+)").unwrap();
+    // i.e. "[6f4c1a9e] (051/052) Accurately ripped"
+    static ref CTDB_RELEASE: Regex = RegexBuilder::new(r"^\s*\[(?P<crc>[0-9a-fA-F]{8})\] \((?P<cm>\d+)/(?P<ct>\d+)\) (?P<status>.+)$").multi_line(true).build().unwrap();
+    // i.e. "  1   | (51/52) Accurately ripped"
+    static ref CTDB_TRACK: Regex = RegexBuilder::new(r"^\s*(?P<track>\d+)\s+\|\s+\((?P<cm>\d+)/(?P<ct>\d+)\) (?P<status>.+)$").multi_line(true).build().unwrap();
+    static ref DIFFERS: Regex = Regex::new(r"[Dd]iffers in (?P<samples>\d+) samples").unwrap();
+    static ref REPAIR: Regex = Regex::new(r"(?i)can be repaired|repair possible").unwrap();
+}
+
+#[derive(Serialize, Deserialize, PartialEq, TS, Clone, Copy)]
+#[ts(export)]
+pub enum CtdbStatus {
+    Match,
+    Differs,
+    Mismatch,
+    NotFound,
+}
+
+#[derive(Serialize, Deserialize, PartialEq, TS, Clone, Copy)]
+#[ts(export)]
+pub struct CtdbConfidence {
+    pub matching: u32,
+    pub total: u32,
+}
+
+#[derive(Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct CtdbTrack {
+    pub num: u8,
+    pub confidence: CtdbConfidence,
+    pub differing_samples: Option<u32>,
+    pub status: CtdbStatus,
+}
+
+#[derive(Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct Ctdb {
+    pub tocid: String,
+    pub crc: String,
+    pub confidence: Option<CtdbConfidence>,
+    pub differing_samples: Option<u32>,
+    pub repair_possible: bool,
+    pub status: CtdbStatus,
+    pub tracks: Vec<CtdbTrack>,
+}
+
+impl CtdbConfidence {
+    pub fn new(matching: u32, total: u32) -> Self {
+        CtdbConfidence { matching, total }
+    }
+}
+
+impl CtdbTrack {
+    pub fn new(num: u8, confidence: CtdbConfidence, differing_samples: Option<u32>, status: CtdbStatus) -> Self {
+        CtdbTrack { num, confidence, differing_samples, status }
+    }
+}
+
+impl CtdbStatus {
+    pub fn from_text(text: &str) -> (CtdbStatus, Option<u32>) {
+        if text.starts_with("Accurately ripped") {
+            return (CtdbStatus::Match, None);
+        }
+
+        match DIFFERS.captures(text) {
+            Some(captures) => (CtdbStatus::Differs, captures.name("samples").unwrap().as_str().parse::<u32>().ok()),
+            None => (CtdbStatus::Mismatch, None),
+        }
+    }
+}
+
+impl Ctdb {
+    pub fn new_notfound(tocid: String) -> Self {
+        Ctdb {
+            tocid,
+            crc: String::default(),
+            confidence: None,
+            differing_samples: None,
+            repair_possible: false,
+            status: CtdbStatus::NotFound,
+            tracks: Vec::new(),
+        }
+    }
+
+    // CUETools writes the same verification block for both the EAC plugin and CUERipper
+    pub fn from_log(log: &str) -> Option<Self> {
+        let tocid_captures = CTDB_TOCID.captures(log)?;
+        let tocid = tocid_captures.name("tocid").unwrap().as_str().trim().to_owned();
+
+        if tocid_captures.name("result").unwrap().as_str().contains("not present") {
+            return Some(Ctdb::new_notfound(tocid));
+        }
+
+        let block = &log[tocid_captures.get(0).unwrap().end()..];
+
+        let tracks: Vec<CtdbTrack> = CTDB_TRACK.captures_iter(block)
+            .map(|captures| {
+                let (status, differing_samples) = CtdbStatus::from_text(captures.name("status").unwrap().as_str().trim());
+                CtdbTrack::new(
+                    captures.name("track").unwrap().as_str().parse::<u8>().unwrap_or_default(),
+                    CtdbConfidence::new(
+                        captures.name("cm").unwrap().as_str().parse::<u32>().unwrap_or_default(),
+                        captures.name("ct").unwrap().as_str().parse::<u32>().unwrap_or_default(),
+                    ),
+                    differing_samples,
+                    status,
+                )
+            })
+            .collect();
+
+        // The first entry is the closest one to the rip
+        let ctdb = match CTDB_RELEASE.captures(block) {
+            Some(captures) => {
+                let status_text = captures.name("status").unwrap().as_str().trim();
+                let (status, differing_samples) = CtdbStatus::from_text(status_text);
+                Ctdb {
+                    tocid,
+                    crc: captures.name("crc").unwrap().as_str().to_uppercase(),
+                    confidence: Some(CtdbConfidence::new(
+                        captures.name("cm").unwrap().as_str().parse::<u32>().unwrap_or_default(),
+                        captures.name("ct").unwrap().as_str().parse::<u32>().unwrap_or_default(),
+                    )),
+                    differing_samples,
+                    repair_possible: status == CtdbStatus::Differs && REPAIR.is_match(block),
+                    status,
+                    tracks,
+                }
+            },
+            None => {
+                let mut ctdb = Ctdb::new_from_tracks(tocid, tracks);
+                ctdb.repair_possible = REPAIR.is_match(block);
+                ctdb
+            },
+        };
+
+        Some(ctdb)
+    }
+
+    // For rippers that only report per-track results
+    pub fn new_from_tracks(tocid: String, tracks: Vec<CtdbTrack>) -> Self {
+        let status = match tracks.is_empty() {
+            true => CtdbStatus::NotFound,
+            false if tracks.iter().all(|t| t.status == CtdbStatus::Match) => CtdbStatus::Match,
+            false if tracks.iter().all(|t| t.status == CtdbStatus::NotFound) => CtdbStatus::NotFound,
+            false => CtdbStatus::Mismatch,
+        };
+
+        Ctdb {
+            tocid,
+            crc: String::default(),
+            confidence: None,
+            differing_samples: None,
+            repair_possible: false,
+            status,
+            tracks,
+        }
+    }
+}
@@ -0,0 +1,417 @@
+use std::fmt;
+
+use regex::Regex;
+use serde::{Serialize, Deserialize};
+use ts_rs::TS;
+
+use crate::{error::CambiaError, toc::{TocEntry, TocRaw, TrackType}, util::Time};
+
+pub mod write;
+pub mod compare;
+
+// CUE times are MM:SS:FF with 75 frames (sectors) per second
+static FRAMES_PER_SECOND: u32 = 75;
+
+lazy_static! {
+    static ref MSF: Regex = Regex::new(r"^(?P<m>\d+):(?P<s>\d{1,2}):(?P<f>\d{1,2})$").unwrap();
+    static ref CUE_LINE: Regex = Regex::new(r#"(?mi)^\s*(FILE\s+"?.+"?\s+\w+|TRACK\s+\d+\s+\S+|INDEX\s+\d+\s+\d+:\d+:\d+)\s*$"#).unwrap();
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, Copy, PartialEq, Eq, Debug)]
+#[ts(export)]
+pub enum CueLayout {
+    // One image file for the whole disc
+    SingleFile,
+    // One file per track, gaps at the end of the previous track's file (EAC's "noncompliant" layout)
+    GapsAppended,
+    // One file per track, gaps at the start of their own track's file
+    GapsPrepended,
+    // One file per track, gap audio is not in any file and only known through PREGAP
+    GapsLeftOut,
+    Unknown,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, Copy, PartialEq, Eq, Debug)]
+#[ts(export)]
+pub enum CueFlag {
+    DigitalCopyPermitted,
+    FourChannel,
+    PreEmphasis,
+    SerialCopyManagement,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct CueRem {
+    pub key: String,
+    pub value: String,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct CueFile {
+    pub name: String,
+    pub file_type: String,
+}
+
+// Positions are in frames relative to the start of the file the index is in
+#[derive(Serialize, Deserialize, TS, Clone, Copy, PartialEq, Debug)]
+#[ts(export)]
+pub struct CueIndex {
+    pub num: u32,
+    pub file: usize,
+    pub position: u32,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct CueTrack {
+    pub num: u32,
+    pub data_type: String,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    pub isrc: Option<String>,
+    pub flags: Vec<CueFlag>,
+    // PREGAP and POSTGAP are silence that isn't part of any file, in frames
+    pub pregap: Option<u32>,
+    pub postgap: Option<u32>,
+    pub indices: Vec<CueIndex>,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct CueSheet {
+    pub catalog: Option<String>,
+    pub title: Option<String>,
+    pub performer: Option<String>,
+    // Disc level REM lines only
+    pub rem: Vec<CueRem>,
+    pub files: Vec<CueFile>,
+    pub tracks: Vec<CueTrack>,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct CueIndexPosition {
+    pub num: u32,
+    pub sector: u32,
+}
+
+// Absolute disc positions of a track, in sectors
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq)]
+#[ts(export)]
+pub struct CueTrackPosition {
+    pub num: u32,
+    pub start_sector: u32,
+    pub pregap_length: Time,
+    pub indices: Vec<CueIndexPosition>,
+}
+
+// Reasons a sheet can't be turned into disc positions
+#[derive(Clone, PartialEq, Debug)]
+pub enum CueTocError {
+    NoTracks,
+    UnsupportedLayout(CueLayout),
+    // Positions in a later file depend on the length of every file before it
+    MissingFileLength(String),
+    MissingIndex(u32),
+    OutOfOrder(u32),
+    // Sector positions past what fits in a u32
+    TooLong,
+}
+
+impl fmt::Display for CueTocError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            CueTocError::NoTracks => write!(f, "The CUE sheet has no tracks"),
+            CueTocError::UnsupportedLayout(layout) => write!(f, "The TOC can't be rebuilt from a {:?} layout", layout),
+            CueTocError::MissingFileLength(name) => write!(f, "The length of {} is required to rebuild the TOC", name),
+            CueTocError::MissingIndex(track) => write!(f, "Track {} has no INDEX 01", track),
+            CueTocError::OutOfOrder(track) => write!(f, "Track {} starts before the previous track", track),
+            CueTocError::TooLong => write!(f, "The CUE sheet's positions don't fit on a disc"),
+        }
+    }
+}
+
+impl From<CueTocError> for CambiaError {
+    fn from(e: CueTocError) -> Self {
+        CambiaError::new_anon(&e.to_string())
+    }
+}
+
+impl CueTrack {
+    fn new(num: u32, data_type: String) -> Self {
+        CueTrack {
+            num,
+            data_type,
+            title: None,
+            performer: None,
+            isrc: None,
+            flags: Vec::new(),
+            pregap: None,
+            postgap: None,
+            indices: Vec::new(),
+        }
+    }
+
+    pub fn index(&self, num: u32) -> Option<&CueIndex> {
+        self.indices.iter().find(|index| index.num == num)
+    }
+
+    pub fn is_audio(&self) -> bool {
+        self.data_type.eq_ignore_ascii_case("AUDIO")
+    }
+}
+
+impl CueFlag {
+    fn parse(flag: &str) -> Option<Self> {
+        match flag.to_ascii_uppercase().as_str() {
+            "DCP" => Some(CueFlag::DigitalCopyPermitted),
+            "4CH" => Some(CueFlag::FourChannel),
+            "PRE" => Some(CueFlag::PreEmphasis),
+            "SCMS" => Some(CueFlag::SerialCopyManagement),
+            _ => None,
+        }
+    }
+}
+
+// Heuristic for text that was uploaded as a log but is really a CUE sheet
+pub fn is_cue_sheet(text: &str) -> bool {
+    CUE_LINE.find_iter(text).take(3).count() == 3
+}
+
+impl CueSheet {
+    pub fn parse(text: &str) -> Result<Self, CambiaError> {
+        let mut sheet = CueSheet {
+            catalog: None,
+            title: None,
+            performer: None,
+            rem: Vec::new(),
+            files: Vec::new(),
+            tracks: Vec::new(),
+        };
+
+        for (idx, line) in text.trim_start_matches('\u{feff}').lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let (keyword, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+            let rest = rest.trim();
+            let invalid = |message: &str| CambiaError::new_anon(&format!("Invalid CUE sheet at line {}: {}", idx + 1, message));
+
+            match keyword.to_ascii_uppercase().as_str() {
+                "CATALOG" => sheet.catalog = Some(unquote(rest)),
+                "REM" if sheet.tracks.is_empty() => {
+                    let (key, value) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                    sheet.rem.push(CueRem { key: key.to_owned(), value: unquote(value.trim()) });
+                },
+                "TITLE" | "PERFORMER" => {
+                    let value = Some(unquote(rest));
+                    let is_title = keyword.eq_ignore_ascii_case("TITLE");
+                    match sheet.tracks.last_mut() {
+                        Some(track) if is_title => track.title = value,
+                        Some(track) => track.performer = value,
+                        None if is_title => sheet.title = value,
+                        None => sheet.performer = value,
+                    }
+                },
+                "FILE" => {
+                    let (name, file_type) = split_file(rest).ok_or_else(|| invalid("malformed FILE"))?;
+                    sheet.files.push(CueFile { name, file_type });
+                },
+                "TRACK" => {
+                    if sheet.files.is_empty() {
+                        return Err(invalid("TRACK before FILE"));
+                    }
+                    let (num, data_type) = rest.split_once(char::is_whitespace).ok_or_else(|| invalid("malformed TRACK"))?;
+                    let num = num.parse::<u32>().map_err(|_| invalid("malformed track number"))?;
+                    sheet.tracks.push(CueTrack::new(num, data_type.trim().to_ascii_uppercase()));
+                },
+                "INDEX" => {
+                    let file = sheet.files.len().saturating_sub(1);
+                    let track = sheet.tracks.last_mut().ok_or_else(|| invalid("INDEX before TRACK"))?;
+                    let (num, position) = rest.split_once(char::is_whitespace).ok_or_else(|| invalid("malformed INDEX"))?;
+                    let num = num.parse::<u32>().map_err(|_| invalid("malformed index number"))?;
+                    let position = parse_msf(position.trim()).ok_or_else(|| invalid("malformed index time"))?;
+                    track.indices.push(CueIndex { num, file, position });
+                },
+                "PREGAP" | "POSTGAP" => {
+                    let length = parse_msf(rest).ok_or_else(|| invalid("malformed gap time"))?;
+                    let track = sheet.tracks.last_mut().ok_or_else(|| invalid("gap before TRACK"))?;
+                    if keyword.eq_ignore_ascii_case("PREGAP") {
+                        track.pregap = Some(length);
+                    } else {
+                        track.postgap = Some(length);
+                    }
+                },
+                "FLAGS" => {
+                    let track = sheet.tracks.last_mut().ok_or_else(|| invalid("FLAGS before TRACK"))?;
+                    track.flags = rest.split_whitespace().filter_map(CueFlag::parse).collect();
+                },
+                "ISRC" => {
+                    let track = sheet.tracks.last_mut().ok_or_else(|| invalid("ISRC before TRACK"))?;
+                    track.isrc = Some(unquote(rest));
+                },
+                // SONGWRITER, CDTEXTFILE, track level REM and unknown commands carry nothing the TOC needs
+                _ => (),
+            }
+        }
+
+        Ok(sheet)
+    }
+
+    pub fn layout(&self) -> CueLayout {
+        match self.files.len() {
+            0 => CueLayout::Unknown,
+            1 => CueLayout::SingleFile,
+            _ => {
+                let gap_in_previous_file = self.tracks.iter().any(|track| match (track.index(0), track.index(1)) {
+                    (Some(gap), Some(start)) => gap.file < start.file,
+                    _ => false,
+                });
+                // The first track's gap is always in its own file (HTOA)
+                let gap_in_own_file = self.tracks.iter().skip(1).any(|track| match (track.index(0), track.index(1)) {
+                    (Some(gap), Some(start)) => gap.file == start.file && gap.position == 0 && start.position > 0,
+                    _ => false,
+                });
+
+                match (gap_in_previous_file, gap_in_own_file) {
+                    (true, false) => CueLayout::GapsAppended,
+                    (false, true) => CueLayout::GapsPrepended,
+                    (true, true) => CueLayout::Unknown,
+                    (false, false) if self.tracks.iter().skip(1).any(|track| track.pregap.is_some()) => CueLayout::GapsLeftOut,
+                    // No gaps on the disc, every file holds exactly one track
+                    (false, false) => CueLayout::GapsAppended,
+                }
+            },
+        }
+    }
+
+    // File lengths are in sectors, in the order of the FILE lines
+    // Single file sheets don't need any, split sheets need the length of every file before the last track
+    pub fn positions(&self, file_lengths: &[u32]) -> Result<Vec<CueTrackPosition>, CueTocError> {
+        match self.layout() {
+            CueLayout::SingleFile | CueLayout::GapsAppended | CueLayout::GapsPrepended => (),
+            layout => return Err(CueTocError::UnsupportedLayout(layout)),
+        }
+        if self.tracks.is_empty() {
+            return Err(CueTocError::NoTracks);
+        }
+
+        let mut positions: Vec<CueTrackPosition> = Vec::new();
+        // PREGAP silence pushes everything after it back
+        let mut silence: u32 = 0;
+
+        for track in self.tracks.iter() {
+            silence = silence.checked_add(track.pregap.unwrap_or_default()).ok_or(CueTocError::TooLong)?;
+
+            let mut indices: Vec<CueIndexPosition> = Vec::new();
+            for index in track.indices.iter() {
+                let sector = self.file_start(index.file, file_lengths)?
+                    .checked_add(index.position)
+                    .and_then(|sector| sector.checked_add(silence))
+                    .ok_or(CueTocError::TooLong)?;
+                indices.push(CueIndexPosition { num: index.num, sector });
+            }
+
+            let start_sector = indices.iter().find(|index| index.num == 1).ok_or(CueTocError::MissingIndex(track.num))?.sector;
+            if positions.last().is_some_and(|prev| prev.start_sector >= start_sector) {
+                return Err(CueTocError::OutOfOrder(track.num));
+            }
+
+            let gap = indices.iter().find(|index| index.num == 0).map_or(0, |index| start_sector.saturating_sub(index.sector));
+            let pregap = gap + track.pregap.unwrap_or_default();
+            positions.push(CueTrackPosition {
+                num: track.num,
+                start_sector,
+                pregap_length: sectors_to_time(pregap),
+                indices,
+            });
+
+            silence = silence.checked_add(track.postgap.unwrap_or_default()).ok_or(CueTocError::TooLong)?;
+        }
+
+        Ok(positions)
+    }
+
+    // Same as the positions, plus the length of the last file for the lead-out
+    pub fn toc(&self, file_lengths: &[u32]) -> Result<TocRaw, CueTocError> {
+        let positions = self.positions(file_lengths)?;
+
+        let last_file = self.files.len() - 1;
+        let last_length = file_lengths.get(last_file).ok_or_else(|| CueTocError::MissingFileLength(self.files[last_file].name.clone()))?;
+        let silence = self.tracks.iter()
+            .flat_map(|track| [track.pregap, track.postgap])
+            .try_fold(0u32, |sum, gap| sum.checked_add(gap.unwrap_or_default()))
+            .ok_or(CueTocError::TooLong)?;
+        let lead_out = self.file_start(last_file, file_lengths)?
+            .checked_add(*last_length)
+            .and_then(|sector| sector.checked_add(silence))
+            .ok_or(CueTocError::TooLong)?;
+
+        let mut entries: Vec<TocEntry> = Vec::new();
+        for (idx, position) in positions.iter().enumerate() {
+            let end = positions.get(idx + 1).map_or(lead_out, |next| next.start_sector);
+            if end <= position.start_sector {
+                return Err(CueTocError::OutOfOrder(position.num));
+            }
+            let end_sector = end - 1;
+            let mut entry = TocEntry::new(
+                position.num,
+                sectors_to_time(position.start_sector),
+                sectors_to_time(end - position.start_sector),
+                position.start_sector,
+                end_sector,
+            );
+            if self.tracks.iter().any(|track| track.num == position.num && !track.is_audio()) {
+                entry.track_type = TrackType::Data;
+            }
+            entries.push(entry);
+        }
+
+        Ok(TocRaw::new(entries))
+    }
+
+    fn file_start(&self, file: usize, file_lengths: &[u32]) -> Result<u32, CueTocError> {
+        self.files[..file].iter()
+            .enumerate()
+            .try_fold(0u32, |start, (idx, prev)| match file_lengths.get(idx) {
+                Some(length) => start.checked_add(*length).ok_or(CueTocError::TooLong),
+                None => Err(CueTocError::MissingFileLength(prev.name.clone())),
+            })
+    }
+}
+
+fn parse_msf(msf: &str) -> Option<u32> {
+    let c = MSF.captures(msf)?;
+    let m: u32 = c.name("m")?.as_str().parse().ok()?;
+    let s: u32 = c.name("s")?.as_str().parse().ok()?;
+    let f: u32 = c.name("f")?.as_str().parse().ok()?;
+
+    if s >= 60 || f >= FRAMES_PER_SECOND {
+        return None;
+    }
+
+    m.checked_mul(60)?.checked_add(s)?.checked_mul(FRAMES_PER_SECOND)?.checked_add(f)
+}
+
+fn sectors_to_time(sectors: u32) -> Time {
+    Time::from_ss(&(f64::from(sectors) / f64::from(FRAMES_PER_SECOND)).to_string())
+}
+
+// Logs print times in hundredths of a second, so this is only exact to a sector
+fn time_to_sectors(time: Time) -> u32 {
+    (time.as_secs_f64() * f64::from(FRAMES_PER_SECOND)).round() as u32
+}
+
+fn unquote(value: &str) -> String {
+    value.trim().trim_matches('"').to_owned()
+}
+
+// FILE "name with spaces.flac" WAVE, the name may also be unquoted
+fn split_file(rest: &str) -> Option<(String, String)> {
+    let (name, file_type) = rest.trim().rsplit_once(char::is_whitespace)?;
+    Some((unquote(name), file_type.trim().to_ascii_uppercase()))
+}
@@ -0,0 +1,142 @@
+use serde::{Serialize, Deserialize};
+use ts_rs::TS;
+
+use crate::parser::ParsedLog;
+
+use super::{time_to_sectors, CueFlag, CueSheet, CueTrackPosition};
+
+// Logs print pregaps in hundredths of a second, which can be a sector off after rounding
+static PREGAP_TOLERANCE: u32 = 1;
+
+#[derive(Serialize, Deserialize, TS, Clone, Copy, PartialEq, Eq, Debug)]
+#[ts(export)]
+pub enum CueMismatchKind {
+    TrackCount,
+    IndexOffset,
+    Pregap,
+    PreEmphasis,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct CueMismatch {
+    pub kind: CueMismatchKind,
+    pub track: Option<u32>,
+    pub message: String,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct CueComparison {
+    pub mismatches: Vec<CueMismatch>,
+    // Why index offsets and pregaps were not compared, if they weren't
+    pub unchecked: Option<String>,
+}
+
+impl CueMismatch {
+    fn new(kind: CueMismatchKind, track: Option<u32>, message: String) -> Self {
+        CueMismatch { kind, track, message }
+    }
+}
+
+impl CueComparison {
+    pub fn is_match(&self) -> bool {
+        self.mismatches.is_empty()
+    }
+}
+
+// File lengths are only needed for split sheets, see `CueSheet::positions`
+pub fn compare(cue: &CueSheet, parsed_log: &ParsedLog, file_lengths: &[u32]) -> CueComparison {
+    let mut mismatches: Vec<CueMismatch> = Vec::new();
+
+    mismatches.append(&mut compare_track_count(cue, parsed_log));
+    mismatches.append(&mut compare_preemphasis(cue, parsed_log));
+
+    let unchecked = match cue.positions(file_lengths) {
+        Ok(positions) => {
+            mismatches.append(&mut compare_index_offsets(&positions, parsed_log));
+            mismatches.append(&mut compare_pregaps(&positions, parsed_log));
+            None
+        },
+        Err(e) => Some(e.to_string()),
+    };
+
+    CueComparison { mismatches, unchecked }
+}
+
+// Rippers leave the data tracks of enhanced CDs out of the sheet
+fn compare_track_count(cue: &CueSheet, parsed_log: &ParsedLog) -> Vec<CueMismatch> {
+    let toc_tracks = parsed_log.toc.raw.audio_entries().len();
+    let cue_tracks = cue.tracks.iter().filter(|track| track.is_audio()).count();
+
+    if toc_tracks == cue_tracks {
+        return Vec::new();
+    }
+
+    vec![CueMismatch::new(
+        CueMismatchKind::TrackCount,
+        None,
+        format!("The CUE sheet has {} audio tracks, the log's TOC has {}", cue_tracks, toc_tracks),
+    )]
+}
+
+fn compare_preemphasis(cue: &CueSheet, parsed_log: &ParsedLog) -> Vec<CueMismatch> {
+    cue.tracks.iter()
+        .filter_map(|cue_track| {
+            let track = parsed_log.tracks.iter().find(|track| u32::from(track.num) == cue_track.num)?;
+            let log_preemphasis = track.preemphasis?;
+            let cue_preemphasis = cue_track.flags.contains(&CueFlag::PreEmphasis);
+
+            (log_preemphasis != cue_preemphasis).then(|| CueMismatch::new(
+                CueMismatchKind::PreEmphasis,
+                Some(cue_track.num),
+                format!(
+                    "Pre-emphasis is {} in the log but the PRE flag is {} in the CUE sheet",
+                    if log_preemphasis { "on" } else { "off" },
+                    if cue_preemphasis { "set" } else { "not set" },
+                ),
+            ))
+        })
+        .collect()
+}
+
+// Offsets are compared relative to the first track, images don't have to start at sector 0
+fn compare_index_offsets(positions: &[CueTrackPosition], parsed_log: &ParsedLog) -> Vec<CueMismatch> {
+    let entries = &parsed_log.toc.raw.entries;
+    let (Some(cue_first), Some(toc_first)) = (positions.first(), entries.first()) else {
+        return Vec::new();
+    };
+
+    positions.iter()
+        .filter_map(|position| {
+            let entry = entries.iter().find(|entry| entry.track == position.num)?;
+            let cue_offset = position.start_sector.checked_sub(cue_first.start_sector)?;
+            let toc_offset = entry.start_sector.checked_sub(toc_first.start_sector)?;
+
+            (cue_offset != toc_offset).then(|| CueMismatch::new(
+                CueMismatchKind::IndexOffset,
+                Some(position.num),
+                format!("INDEX 01 is {} sectors into the disc in the CUE sheet, the TOC says {}", cue_offset, toc_offset),
+            ))
+        })
+        .collect()
+}
+
+// The first track is skipped, some logs report the 2 second lead-in as its pregap
+// Tracks without a pregap in the log are skipped too, not every ripper reports them
+fn compare_pregaps(positions: &[CueTrackPosition], parsed_log: &ParsedLog) -> Vec<CueMismatch> {
+    positions.iter()
+        .skip(1)
+        .filter_map(|position| {
+            let track = parsed_log.tracks.iter().find(|track| u32::from(track.num) == position.num)?;
+            let log_pregap = time_to_sectors(track.pregap_length?);
+            let cue_pregap = time_to_sectors(position.pregap_length);
+
+            (log_pregap.abs_diff(cue_pregap) > PREGAP_TOLERANCE).then(|| CueMismatch::new(
+                CueMismatchKind::Pregap,
+                Some(position.num),
+                format!("The pregap is {} sectors in the CUE sheet, the log says {}", cue_pregap, log_pregap),
+            ))
+        })
+        .collect()
+}
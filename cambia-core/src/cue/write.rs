@@ -0,0 +1,143 @@
+use std::fmt::Write;
+
+use crate::{error::CambiaError, extract::Gap, parser::ParsedLog, toc::TocEntry, track::TrackEntry};
+
+use super::{time_to_sectors, CueLayout, FRAMES_PER_SECOND};
+
+// `auto` picks the layout from the log's gap handling
+pub fn parse_layout(name: &str) -> Result<Option<CueLayout>, CambiaError> {
+    match name.trim().to_ascii_lowercase().replace('-', "_").as_str() {
+        "" | "auto" => Ok(None),
+        "single" | "single_file" | "image" => Ok(Some(CueLayout::SingleFile)),
+        "appended" | "gaps_appended" => Ok(Some(CueLayout::GapsAppended)),
+        "prepended" | "gaps_prepended" => Ok(Some(CueLayout::GapsPrepended)),
+        _ => Err(CambiaError::new_anon(&format!("Unknown CUE layout: {}. Expected auto, single, appended or prepended.", name))),
+    }
+}
+
+// Layout the rip was done with, range rips are always a single image
+pub fn log_layout(parsed_log: &ParsedLog) -> Option<CueLayout> {
+    if parsed_log.tracks.first().is_some_and(|track| track.is_range) {
+        return Some(CueLayout::SingleFile);
+    }
+
+    match parsed_log.gap_handling {
+        Gap::Append | Gap::AppendNoHtoa | Gap::AppendUndetected => Some(CueLayout::GapsAppended),
+        Gap::Prepend => Some(CueLayout::GapsPrepended),
+        Gap::Discard | Gap::Unknown | Gap::Inapplicable => None,
+    }
+}
+
+// Rebuilds the CUE sheet of a rip from the TOC, the pregaps and the filenames in its log
+// A missing layout is taken from the log, data tracks are left out like rippers do
+pub fn write_cue(parsed_log: &ParsedLog, layout: Option<CueLayout>) -> Result<String, CambiaError> {
+    let layout = match layout.or_else(|| log_layout(parsed_log)) {
+        Some(layout @ (CueLayout::SingleFile | CueLayout::GapsAppended | CueLayout::GapsPrepended)) => layout,
+        Some(layout) => return Err(CambiaError::new_anon(&format!("CUE sheets can't be written with a {:?} layout", layout))),
+        None => return Err(CambiaError::new_anon("The log's gap handling doesn't map to a CUE layout, pick one explicitly")),
+    };
+
+    let raw = &parsed_log.toc.raw;
+    let entries = raw.audio_entries();
+    let Some(first) = entries.first() else {
+        return Err(CambiaError::new_anon("The log has no TOC to build a CUE sheet from"));
+    };
+
+    let mut cue = String::new();
+    writeln!(cue, "REM COMMENT \"Rebuilt by cambia from a {} log\"", parsed_log.ripper).unwrap();
+    writeln!(cue, "PERFORMER \"{}\"", quote(&parsed_log.release_info.artist)).unwrap();
+    writeln!(cue, "TITLE \"{}\"", quote(&parsed_log.release_info.title)).unwrap();
+
+    if layout == CueLayout::SingleFile {
+        let name = parsed_log.tracks.first().and_then(|track| track.filenames.first()).map_or_else(|| String::from("Range.wav"), |name| basename(name));
+        writeln!(cue, "FILE \"{}\" WAVE", quote(&name)).unwrap();
+    }
+
+    for (idx, entry) in entries.iter().enumerate() {
+        let track = parsed_log.tracks.iter().find(|track| u32::from(track.num) == entry.track);
+        let pregap = pregap_sectors(entry, first, track);
+        let file = track.and_then(|track| track.filenames.first()).map_or_else(|| format!("{:02}.wav", entry.track), |name| basename(name));
+
+        match layout {
+            CueLayout::GapsAppended => {
+                // The gap is at the end of the previous file, only the first track has no previous file
+                if idx == 0 {
+                    writeln!(cue, "FILE \"{}\" WAVE", quote(&file)).unwrap();
+                    write_track(&mut cue, entry, track);
+                    write_pregap(&mut cue, pregap);
+                } else {
+                    let prev = &entries[idx - 1];
+                    write_track(&mut cue, entry, track);
+                    if pregap > 0 {
+                        writeln!(cue, "    INDEX 00 {}", msf(entry.start_sector.saturating_sub(pregap).saturating_sub(prev.start_sector))).unwrap();
+                    }
+                    writeln!(cue, "FILE \"{}\" WAVE", quote(&file)).unwrap();
+                }
+                writeln!(cue, "    INDEX 01 {}", msf(0)).unwrap();
+            },
+            CueLayout::GapsPrepended => {
+                writeln!(cue, "FILE \"{}\" WAVE", quote(&file)).unwrap();
+                write_track(&mut cue, entry, track);
+                if idx == 0 {
+                    write_pregap(&mut cue, pregap);
+                    writeln!(cue, "    INDEX 01 {}", msf(0)).unwrap();
+                } else if pregap > 0 {
+                    writeln!(cue, "    INDEX 00 {}", msf(0)).unwrap();
+                    writeln!(cue, "    INDEX 01 {}", msf(pregap)).unwrap();
+                } else {
+                    writeln!(cue, "    INDEX 01 {}", msf(0)).unwrap();
+                }
+            },
+            _ => {
+                // The image starts at the first track's INDEX 01
+                let position = entry.start_sector.saturating_sub(first.start_sector);
+                write_track(&mut cue, entry, track);
+                if idx == 0 {
+                    write_pregap(&mut cue, pregap);
+                } else if pregap > 0 {
+                    writeln!(cue, "    INDEX 00 {}", msf(position.saturating_sub(pregap))).unwrap();
+                }
+                writeln!(cue, "    INDEX 01 {}", msf(position)).unwrap();
+            },
+        }
+    }
+
+    Ok(cue)
+}
+
+fn write_track(cue: &mut String, entry: &TocEntry, track: Option<&TrackEntry>) {
+    writeln!(cue, "  TRACK {:02} AUDIO", entry.track).unwrap();
+    if track.is_some_and(|track| track.preemphasis == Some(true)) {
+        writeln!(cue, "    FLAGS PRE").unwrap();
+    }
+}
+
+// Audio in front of the first track (HTOA) is not part of any ripped file
+fn write_pregap(cue: &mut String, pregap: u32) {
+    if pregap > 0 {
+        writeln!(cue, "    PREGAP {}", msf(pregap)).unwrap();
+    }
+}
+
+// The first track's pregap is its TOC start, the 2 second lead-in some logs report is not on the disc
+fn pregap_sectors(entry: &TocEntry, first: &TocEntry, track: Option<&TrackEntry>) -> u32 {
+    if entry.track == first.track {
+        return first.start_sector;
+    }
+
+    track.and_then(|track| track.pregap_length).map_or(0, time_to_sectors)
+}
+
+fn msf(sectors: u32) -> String {
+    let seconds = sectors / FRAMES_PER_SECOND;
+    format!("{:02}:{:02}:{:02}", seconds / 60, seconds % 60, sectors % FRAMES_PER_SECOND)
+}
+
+// Logs have either bare filenames or full paths from the ripping machine
+fn basename(path: &str) -> String {
+    path.rsplit(['/', '\\']).next().unwrap_or(path).trim().to_owned()
+}
+
+fn quote(value: &str) -> String {
+    value.replace('"', "'")
+}
@@ -0,0 +1,198 @@
+use regex::{Regex, RegexBuilder};
+
+use crate::{error::CambiaError, extract::Ripper};
+
+// A ripper needs at least this much evidence before it is considered at all
+static MIN_SCORE: u32 = 6;
+// The best candidate has to own this share of the total score to win outright
+static MIN_CONFIDENCE: f64 = 0.6;
+// Headers this close to a log start belong to the same log (i.e. EAC's second header line)
+static HEADER_SPAN: usize = 4;
+
+struct Marker {
+    ripper: Ripper,
+    regex: Regex,
+    weight: u32,
+    // Header markers mark where the log itself begins
+    header: bool,
+}
+
+impl Marker {
+    fn new(ripper: Ripper, pattern: &str, weight: u32, header: bool) -> Self {
+        Marker {
+            ripper,
+            regex: RegexBuilder::new(pattern).multi_line(true).build().unwrap(),
+            weight,
+            header,
+        }
+    }
+}
+
+lazy_static! {
+    static ref SEPARATOR_TAIL: Regex = Regex::new(r"(\s*-{20,})*\s*$").unwrap();
+
+    // Markers only contain text that survives EAC's translations or is unique to a single ripper
+    static ref MARKERS: Vec<Marker> = vec![
+        Marker::new(Ripper::EAC, r"Exact Audio Copy V\d", 10, true),
+        Marker::new(Ripper::EAC, r"EAC extraction logfile from", 8, true),
+        Marker::new(Ripper::EAC, r"^==== .+ [0-9A-F]{64} ====\r?$", 8, false),
+        Marker::new(Ripper::EAC, r"^\s*(Copy|Test) CRC [0-9A-F]{8}", 3, false),
+
+        Marker::new(Ripper::XLD, r"X Lossless Decoder version", 10, true),
+        Marker::new(Ripper::XLD, r"XLD extraction logfile from", 8, true),
+        Marker::new(Ripper::XLD, r"-----BEGIN XLD SIGNATURE-----", 8, false),
+        Marker::new(Ripper::XLD, r"CRC32 hash( \(test run\))?\s*: [0-9A-F]{8}", 3, false),
+
+        Marker::new(Ripper::Whipper, r"Log created by: whipper", 10, true),
+        Marker::new(Ripper::Whipper, r"^Ripping phase information:", 4, false),
+        Marker::new(Ripper::Whipper, r"^SHA-256 hash: [0-9A-Fa-f]{64}", 4, false),
+        Marker::new(Ripper::Whipper, r"^Conclusive status report:", 2, false),
+
+        Marker::new(Ripper::CueRipper, r"CUERipper v\d", 10, true),
+        Marker::new(Ripper::CueRipper, r"\[AccurateRip ID: [0-9a-f\-]+\]", 3, false),
+        Marker::new(Ripper::CueRipper, r"\[CTDB TOCID: ", 2, false),
+
+        Marker::new(Ripper::DBPA, r"dBpoweramp Release", 10, true),
+        Marker::new(Ripper::DBPA, r"Ripping with drive '", 4, false),
+        Marker::new(Ripper::DBPA, r"FUA Cache Invalidate:", 3, false),
+
+        Marker::new(Ripper::CyanRip, r"^cyanrip \S+", 10, true),
+        Marker::new(Ripper::CyanRip, r"^Paranoia level:", 3, false),
+        Marker::new(Ripper::CyanRip, r"^Tracks ripped accurately", 3, false),
+
+        Marker::new(Ripper::EZCD, r"^EZ CD Audio Converter \d", 10, true),
+
+        Marker::new(Ripper::Morituri, r"Logfile created by: morituri", 10, true),
+
+        Marker::new(Ripper::Rip, r"Rip [0-9.]+( \(\d+\))? Audio Extraction Log", 10, true),
+
+        Marker::new(Ripper::FreAc, r"^Conversion #\d+", 8, true),
+        Marker::new(Ripper::FreAc, r"fre:ac v?\d", 6, false),
+    ];
+}
+
+pub struct Detection {
+    pub ripper: Ripper,
+    pub score: u32,
+    pub confidence: f64,
+    // Byte offset of the earliest header marker, anything before it isn't part of the log
+    pub start: usize,
+}
+
+impl Detection {
+    fn new(ripper: Ripper) -> Self {
+        Detection {
+            ripper,
+            score: 0,
+            confidence: 0.0,
+            start: 0,
+        }
+    }
+}
+
+// Scores every ripper against the whole document, best candidate first
+pub fn detect(text: &str) -> Vec<Detection> {
+    let mut detections: Vec<Detection> = Vec::new();
+    let mut header_starts: Vec<(Ripper, usize)> = Vec::new();
+
+    for marker in MARKERS.iter() {
+        let Some(m) = marker.regex.find(text) else {
+            continue;
+        };
+
+        if marker.header {
+            header_starts.push((marker.ripper, m.start()));
+        }
+
+        match detections.iter_mut().find(|d| d.ripper == marker.ripper) {
+            Some(detection) => detection.score += marker.weight,
+            None => {
+                let mut detection = Detection::new(marker.ripper);
+                detection.score = marker.weight;
+                detections.push(detection);
+            },
+        }
+    }
+
+    let total: u32 = detections.iter().map(|d| d.score).sum();
+    for detection in detections.iter_mut() {
+        detection.confidence = f64::from(detection.score) / f64::from(total);
+        detection.start = header_starts.iter()
+            .filter(|(ripper, _)| *ripper == detection.ripper)
+            .map(|(_, start)| *start)
+            .min()
+            .unwrap_or_default();
+    }
+
+    detections.sort_by_key(|d| std::cmp::Reverse(d.score));
+    detections
+}
+
+pub fn detect_best(text: &str) -> Result<Detection, CambiaError> {
+    let candidates: Vec<Detection> = detect(text).into_iter().filter(|d| d.score >= MIN_SCORE).collect();
+
+    match candidates.first() {
+        Some(best) if best.confidence >= MIN_CONFIDENCE => Ok(candidates.into_iter().next().unwrap()),
+        Some(_) => {
+            let names = candidates.iter().map(|d| d.ripper.to_string()).collect::<Vec<String>>().join(", ");
+            Err(CambiaError::new_anon(&format!("Ambiguous file, could be any of: {names}.")))
+        },
+        None if crate::cue::is_cue_sheet(text) => Err(CambiaError::new_anon("This is a CUE sheet, not a rip log.")),
+        None => Err(CambiaError::new_anon("Unsupported file.")),
+    }
+}
+
+// Splits concatenated logs on the header markers of every ripper, separators are left out
+pub fn split_logs(text: &str) -> Vec<&str> {
+    let mut starts: Vec<usize> = MARKERS.iter()
+        .filter(|marker| marker.header)
+        .flat_map(|marker| marker.regex.find_iter(text))
+        .map(|m| m.start())
+        .filter(|start| at_line_start(text, *start))
+        .collect();
+    starts.sort_unstable();
+    starts.dedup();
+
+    let mut boundaries: Vec<usize> = Vec::new();
+    for start in starts {
+        match boundaries.last() {
+            Some(prev) if text[*prev..start].lines().filter(|line| !line.trim().is_empty()).count() <= HEADER_SPAN => (),
+            _ => boundaries.push(start),
+        }
+    }
+
+    let Some(first) = boundaries.first().copied() else {
+        return vec![text];
+    };
+
+    // Text in front of the first header is either a log without a header line or junk
+    // Junk is left attached so that detection can strip it
+    let prefix = text[..first].trim();
+    if !prefix.is_empty() && detect_best(prefix).is_err() {
+        boundaries[0] = 0;
+    } else if !prefix.is_empty() {
+        boundaries.insert(0, 0);
+    }
+
+    boundaries.iter()
+        .enumerate()
+        .map(|(idx, start)| {
+            let end = boundaries.get(idx + 1).copied().unwrap_or(text.len());
+            trim_separator(&text[*start..end])
+        })
+        .filter(|segment| !segment.trim().is_empty())
+        .collect()
+}
+
+fn at_line_start(text: &str, pos: usize) -> bool {
+    let line_start = text[..pos].rfind('\n').map_or(0, |idx| idx + 1);
+    // Tolerates indentation and mangled BOMs in front of the header
+    text[line_start..pos].chars().all(|c| c.is_whitespace() || !c.is_ascii())
+}
+
+fn trim_separator(segment: &str) -> &str {
+    match SEPARATOR_TAIL.find(segment) {
+        Some(m) => &segment[..m.start()],
+        None => segment,
+    }
+}
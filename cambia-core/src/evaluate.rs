@@ -1,18 +1,44 @@
+use std::{fmt, iter::Sum, ops::{Add, Sub}};
+
 use serde::{Serialize, Deserialize};
 use ts_rs::TS;
 
+use crate::anomaly::Anomaly;
 use crate::parser::{ParsedLogCombined, ParsedLog};
+use crate::remediation::Remediation;
 #[cfg(feature = "gazelle_ev")]
 pub mod gazelle_evaluate;
-// #[cfg(feature = "cambia_ev")]
-// pub mod cambia_evaluate;
+#[cfg(feature = "cambia_ev")]
+pub mod cambia_evaluate;
+#[cfg(feature = "rules_ev")]
+pub mod rule_evaluate;
+pub mod registry;
 
-#[derive(Serialize, Deserialize, TS)]
+#[derive(Serialize, Deserialize, TS, Clone, Copy)]
 #[ts(export)]
 pub enum EvaluatorType {
     Cambia,
     RED,
     OPS,
+    Custom,
+}
+
+// Variants are ordered from best to worst
+#[derive(Serialize, Deserialize, TS, strum_macros::Display, Hash, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+#[ts(export)]
+pub enum Grade {
+    Good,
+    NotIdeal,
+    Bad,
+}
+
+// Points for deductions and scores, grades for grading evaluators
+#[derive(Serialize, Deserialize, TS, Hash, PartialEq, Eq, Clone, Copy, Debug)]
+#[ts(export)]
+pub enum Score {
+    Numeric(i32),
+    Grade(Grade),
+    NotApplicable,
 }
 
 // Ease of grouping on downstream
@@ -72,6 +98,7 @@ pub enum EvaluationUnitField {
     InconsistentErrorSectors,
     DamagedSector,
     Abort,
+    Anomaly,
 }
 
 // This holds the reasoning for the smallest unit of evaluation
@@ -89,21 +116,28 @@ pub struct EvaluationUnitData {
 #[ts(export)]
 pub struct EvaluationCombined {
     pub evaluator: EvaluatorType,
-    pub combined_score: String,
+    // Dated version of the rules that produced the evaluation
+    pub ruleset: String,
+    pub combined_score: Score,
     pub evaluations: Vec<Evaluation>,
 }
 
 #[derive(Serialize, Deserialize, TS)]
 #[ts(export)]
 pub struct Evaluation {
-    pub score: String,
+    pub score: Score,
     pub evaluation_units: Vec<EvaluationUnit>,
+    // Same order as the units, filled in after evaluation
+    pub remediations: Vec<Option<Remediation>>,
+    pub fixed_score: Option<Score>,
+    // The evaluation stopped at a fail deduction, the rest of the log was never looked at
+    pub failed: bool,
 }
 
 #[derive(Serialize, Deserialize, TS, Hash, PartialEq, Eq, Clone)]
 #[ts(export)]
 pub struct EvaluationUnit {
-    pub unit_score: String,
+    pub unit_score: Score,
     pub data: EvaluationUnitData,
 }
 
@@ -119,28 +153,130 @@ impl EvaluationUnitData {
     }
 }
 
+// Anomalies don't carry a score, evaluators decide how much they weigh
+impl From<&Anomaly> for EvaluationUnitData {
+    fn from(anomaly: &Anomaly) -> Self {
+        let scope = match anomaly.track {
+            Some(track) => EvaluationUnitScope::Track(Some(track)),
+            None => EvaluationUnitScope::Release,
+        };
+        EvaluationUnitData::new(scope, EvaluationUnitField::Anomaly, &anomaly.message, EvaluationUnitClass::Critical)
+    }
+}
+
+impl Score {
+    pub fn numeric(&self) -> Option<i32> {
+        match self {
+            Score::Numeric(score) => Some(*score),
+            _ => None,
+        }
+    }
+
+    pub fn grade(&self) -> Option<Grade> {
+        match self {
+            Score::Grade(grade) => Some(*grade),
+            _ => None,
+        }
+    }
+
+    // Lowest number or worst grade, mixing the two isn't meaningful
+    pub fn worst<I: IntoIterator<Item = Score>>(scores: I) -> Score {
+        let mut scores = scores.into_iter();
+        let Some(first) = scores.next() else {
+            return Score::NotApplicable;
+        };
+
+        scores.fold(first, |worst, score| match (worst, score) {
+            (Score::Numeric(a), Score::Numeric(b)) => Score::Numeric(a.min(b)),
+            (Score::Grade(a), Score::Grade(b)) => Score::Grade(a.max(b)),
+            _ => Score::NotApplicable,
+        })
+    }
+}
+
+impl From<i32> for Score {
+    fn from(score: i32) -> Self {
+        Score::Numeric(score)
+    }
+}
+
+impl From<Grade> for Score {
+    fn from(grade: Grade) -> Self {
+        Score::Grade(grade)
+    }
+}
+
+// Arithmetic is only defined on numeric scores
+impl Add for Score {
+    type Output = Score;
+
+    fn add(self, rhs: Score) -> Score {
+        match (self, rhs) {
+            (Score::Numeric(a), Score::Numeric(b)) => Score::Numeric(a + b),
+            _ => Score::NotApplicable,
+        }
+    }
+}
+
+impl Sub for Score {
+    type Output = Score;
+
+    fn sub(self, rhs: Score) -> Score {
+        match (self, rhs) {
+            (Score::Numeric(a), Score::Numeric(b)) => Score::Numeric(a - b),
+            _ => Score::NotApplicable,
+        }
+    }
+}
+
+impl Sum for Score {
+    fn sum<I: Iterator<Item = Score>>(iter: I) -> Score {
+        iter.fold(Score::Numeric(0), |total, score| total + score)
+    }
+}
+
+impl<'a> Sum<&'a Score> for Score {
+    fn sum<I: Iterator<Item = &'a Score>>(iter: I) -> Score {
+        iter.copied().sum()
+    }
+}
+
+impl fmt::Display for Score {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Score::Numeric(score) => write!(f, "{}", score),
+            Score::Grade(grade) => write!(f, "{}", grade),
+            Score::NotApplicable => write!(f, "N/A"),
+        }
+    }
+}
+
 impl EvaluationUnit {
-    pub fn new(unit_score: String, data: EvaluationUnitData) -> Self {
+    pub fn new(unit_score: Score, data: EvaluationUnitData) -> Self {
         EvaluationUnit { unit_score, data }
     }
 
     pub fn new_from_u32(unit_score: u32, data: EvaluationUnitData) -> Self {
-        EvaluationUnit { unit_score: unit_score.to_string(), data }
+        EvaluationUnit { unit_score: Score::Numeric(unit_score as i32), data }
     }
 }
 
 impl Evaluation {
-    pub fn new(score: String, evaluation_units: Vec<EvaluationUnit>) -> Self {
-        Evaluation { score, evaluation_units }
+    pub fn new(score: Score, evaluation_units: Vec<EvaluationUnit>) -> Self {
+        Evaluation { score, evaluation_units, remediations: Vec::new(), fixed_score: None, failed: false }
+    }
+
+    pub fn new_failed(score: Score, evaluation_units: Vec<EvaluationUnit>) -> Self {
+        Evaluation { failed: true, ..Evaluation::new(score, evaluation_units) }
     }
 
     pub fn gazelle_fail(evaluation_units: Vec<EvaluationUnit>) -> Self {
-        Evaluation::new(String::from("-1"), evaluation_units)
+        Evaluation::new_failed(Score::Numeric(-1), evaluation_units)
     }
 }
 
 impl EvaluationCombined {
-    pub fn new(evaluator: EvaluatorType, combined_score: String, evaluations: Vec<Evaluation>) -> Self {
-        EvaluationCombined { evaluator, combined_score, evaluations }
+    pub fn new(evaluator: EvaluatorType, ruleset: &str, combined_score: Score, evaluations: Vec<Evaluation>) -> Self {
+        EvaluationCombined { evaluator, ruleset: ruleset.to_owned(), combined_score, evaluations }
     }
 }
\ No newline at end of file
@@ -1,10 +1,17 @@
+use std::collections::HashMap;
+
 use serde::{Deserialize, Serialize};
 use strum_macros::EnumIter;
 use strum::IntoEnumIterator;
 
-use crate::{parser::{ParsedLogCombined, ParsedLog}, extract::{Quartet, ReadMode, Gap}};
+use crate::{error::CambiaError, parser::{ParsedLogCombined, ParsedLog}, extract::{Quartet, ReadMode, Gap}, drive::{DriveUtils, DriveMatchQuality}};
+
+use super::{EvaluationUnitScope, EvaluationUnitField, EvaluationUnitData, EvaluationUnitClass, Evaluator, EvaluationUnit, EvaluationCombined, Evaluation, EvaluatorType, Grade, Score};
+
+static LOSSY_ENCODERS: [&str; 8] = ["mp3", "lame", "aac", "ogg", "vorbis", "opus", "mpc", "wma"];
 
-use super::{EvaluationUnitScope, EvaluationUnitField, EvaluationUnitData, Evaluator, EvaluationUnit, EvaluationCombined, Evaluation, EvaluatorType};
+// Rulesets before this one graded settings the log doesn't show as bad
+const GRADE_UNVERIFIED_NOT_IDEAL: &str = "2026-10-18";
 
 pub trait CambiaDeductionData {
     fn get_deduction_data(&self) -> EvaluationUnitData;
@@ -27,117 +34,176 @@ pub enum CambiaDeduction {
     IncorrectGapHandling,
 }
 
-// TODO: Will possibly require 4 levels to indicate the really bad settings
-#[derive(Serialize, Deserialize, strum_macros::Display)]
-pub enum CambiaGrade {
-    Good,
-    NotIdeal,
-    Bad,
-}
-
 impl CambiaDeductionData for CambiaDeduction {
     fn get_deduction_data(&self) -> EvaluationUnitData {
         match &self {
             CambiaDeduction::VirtualDrive => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::Drive,
-                "Virtual drives are unlikely to have correct offset, and can be used for deceit"
+                "Virtual drives are unlikely to have correct offset, and can be used for deceit",
+                EvaluationUnitClass::Bad
             ),
             CambiaDeduction::IncorrectReadOffset => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::Offset,
-                "Incorrect read offset for drive"
+                "Incorrect read offset for drive",
+                EvaluationUnitClass::Bad
             ),
             CambiaDeduction::DefeatAudioCacheDisabled => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::Cache,
-                "Audio cache should be defeated/disabled"
+                "Audio cache should be defeated/disabled",
+                EvaluationUnitClass::Bad
             ),
             CambiaDeduction::LossyLog => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::Encoder,
-                "Lossy codecs are not meant for archival"
+                "Lossy codecs are not meant for archival",
+                EvaluationUnitClass::Critical
             ),
             CambiaDeduction::TestAndCopyNotUsed => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::TestAndCopy,
-                "Test and copy was not used"
+                "Test and copy was not used",
+                EvaluationUnitClass::Neutral
             ),
             CambiaDeduction::RipModeNotAccurate => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::ReadMode,
-                "Rip mode should be secure/paranoid"
+                "Rip mode should be secure/paranoid",
+                EvaluationUnitClass::Bad
             ),
             CambiaDeduction::AccurateStreamNotUtilized => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::AccurateStream,
-                "Accurate stream should be utilised"
+                "Accurate stream should be utilised",
+                EvaluationUnitClass::Bad
             ),
             CambiaDeduction::UsedC2 => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::C2,
-                "C2 pointers rely on guesswork and can be used as a DRM tactic"
+                "C2 pointers rely on guesswork and can be used as a DRM tactic",
+                EvaluationUnitClass::Neutral
             ),
             CambiaDeduction::DoesNotFillMissingOffsetSamples => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::Samples,
-                "Does not fill up missing offset samples with silence"
+                "Does not fill up missing offset samples with silence",
+                EvaluationUnitClass::Neutral
             ),
             CambiaDeduction::LeadingTrailingBlocksDeleted => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::SilentBlocks,
-                "Deletes leading and trailing silent blocks"
+                "Deletes leading and trailing silent blocks",
+                EvaluationUnitClass::Bad
             ),
             CambiaDeduction::NullSamplesNotUsed => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::NullSamples,
-                "Null samples should be used in CRC calculations"
+                "Null samples should be used in CRC calculations",
+                EvaluationUnitClass::Neutral
             ),
             CambiaDeduction::NormalizationUsed => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::Normalization,
-                "Normalization during ripping irreversibly alters data and should never be used in this context"
+                "Normalization during ripping irreversibly alters data and should never be used in this context",
+                EvaluationUnitClass::Critical
             ),
             CambiaDeduction::IncorrectGapHandling => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::Gap,
-                "Gaps should be appended"
+                "Gaps should be appended",
+                EvaluationUnitClass::Bad
             ),
         }
     }
 }
 
-pub struct CambiaEvaluator;
+pub struct CambiaEvaluator {
+    ruleset: &'static str,
+}
 
 impl CambiaDeduction {
     // TODO: Requires oversight
-    fn deduct(&self, parsed_log: &ParsedLog) -> EvaluationUnit {
-        let grade: CambiaGrade = match &self {
-            // FIXME: Virtual drive detection
-            CambiaDeduction::VirtualDrive => CambiaGrade::Good,
-            // FIXME: Read offset
-            CambiaDeduction::IncorrectReadOffset => CambiaGrade::Good,
-            CambiaDeduction::DefeatAudioCacheDisabled => if parsed_log.defeat_audio_cache == Quartet::False { CambiaGrade::Bad } else { CambiaGrade::Good },
-            // FIXME: Lossy rip
-            CambiaDeduction::LossyLog => CambiaGrade::Good,
-            CambiaDeduction::TestAndCopyNotUsed => if parsed_log.test_and_copy == Quartet::False { CambiaGrade::Bad } else { CambiaGrade::Good },
-            CambiaDeduction::RipModeNotAccurate => if parsed_log.read_mode != ReadMode::Secure && parsed_log.read_mode != ReadMode::Paranoid { CambiaGrade::Bad } else { CambiaGrade::Good },
-            CambiaDeduction::AccurateStreamNotUtilized => if parsed_log.accurate_stream != Quartet::True { CambiaGrade::Bad } else { CambiaGrade::Good },
-            CambiaDeduction::UsedC2 => if parsed_log.use_c2 == Quartet::True { CambiaGrade::Bad } else { CambiaGrade::Good },
-            CambiaDeduction::DoesNotFillMissingOffsetSamples => if parsed_log.fill_silence == Quartet::False { CambiaGrade::Bad } else { CambiaGrade::Good },
-            CambiaDeduction::LeadingTrailingBlocksDeleted => if parsed_log.delete_silence == Quartet::True { CambiaGrade::Bad } else { CambiaGrade::Good },
-            CambiaDeduction::NullSamplesNotUsed => if parsed_log.use_null_samples == Quartet::False { CambiaGrade::Bad } else { CambiaGrade::Good },
-            CambiaDeduction::NormalizationUsed => if parsed_log.normalize == Quartet::True || parsed_log.normalize == Quartet::Unknown { CambiaGrade::Bad } else { CambiaGrade::Good },
-            CambiaDeduction::IncorrectGapHandling => if parsed_log.gap_handling != Gap::Append && parsed_log.gap_handling != Gap::AppendNoHtoa { CambiaGrade::Bad } else { CambiaGrade::Good },
+    fn deduct(&self, parsed_log: &ParsedLog, ruleset: &str) -> EvaluationUnit {
+        let lenient = ruleset >= GRADE_UNVERIFIED_NOT_IDEAL;
+        // TODO: Will possibly require 4 levels to indicate the really bad settings
+        let grade: Grade = match &self {
+            CambiaDeduction::VirtualDrive => if parsed_log.drive.to_lowercase().contains("generic dvd-rom scsi cdrom device") { Grade::Bad } else { Grade::Good },
+            CambiaDeduction::IncorrectReadOffset => match (DriveUtils::fuzzy_search_model(parsed_log.drive.clone()), parsed_log.read_offset) {
+                (DriveMatchQuality::STRONG(Some(matched_offset)), Some(read_offset)) if matched_offset != read_offset => Grade::Bad,
+                // Can't be verified but a combined offset is still preferable to no offset
+                (_, None) if parsed_log.combined_rw_offset.is_some() => Grade::NotIdeal,
+                _ => Grade::Good,
+            },
+            CambiaDeduction::DefeatAudioCacheDisabled => if parsed_log.defeat_audio_cache == Quartet::False { Grade::Bad } else { Grade::Good },
+            CambiaDeduction::LossyLog => {
+                let lossy = parsed_log.audio_encoder.iter().filter(|encoder| LOSSY_ENCODERS.iter().any(|lossy| encoder.to_lowercase().contains(lossy))).count();
+                match lossy {
+                    0 => Grade::Good,
+                    n if n == parsed_log.audio_encoder.len() => Grade::Bad,
+                    _ => Grade::NotIdeal,
+                }
+            },
+            CambiaDeduction::TestAndCopyNotUsed => match parsed_log.test_and_copy {
+                Quartet::False if lenient => Grade::NotIdeal,
+                Quartet::False => Grade::Bad,
+                _ => Grade::Good,
+            },
+            CambiaDeduction::RipModeNotAccurate => if parsed_log.read_mode != ReadMode::Secure && parsed_log.read_mode != ReadMode::Paranoid { Grade::Bad } else { Grade::Good },
+            // Only a setting the log shows as wrong is bad, one it doesn't show can't be held against the rip
+            CambiaDeduction::AccurateStreamNotUtilized => match parsed_log.accurate_stream {
+                Quartet::True => Grade::Good,
+                Quartet::False => Grade::Bad,
+                Quartet::Unknown | Quartet::Unsupported if lenient => Grade::NotIdeal,
+                Quartet::Unknown | Quartet::Unsupported => Grade::Bad,
+            },
+            CambiaDeduction::UsedC2 => if parsed_log.use_c2 == Quartet::True { Grade::Bad } else { Grade::Good },
+            CambiaDeduction::DoesNotFillMissingOffsetSamples => if parsed_log.fill_silence == Quartet::False { Grade::Bad } else { Grade::Good },
+            CambiaDeduction::LeadingTrailingBlocksDeleted => if parsed_log.delete_silence == Quartet::True { Grade::Bad } else { Grade::Good },
+            CambiaDeduction::NullSamplesNotUsed => if parsed_log.use_null_samples == Quartet::False { Grade::Bad } else { Grade::Good },
+            CambiaDeduction::NormalizationUsed => if parsed_log.normalize == Quartet::True || parsed_log.normalize == Quartet::Unknown { Grade::Bad } else { Grade::Good },
+            CambiaDeduction::IncorrectGapHandling => match parsed_log.gap_handling {
+                Gap::Append | Gap::AppendNoHtoa => Grade::Good,
+                Gap::Prepend | Gap::Discard => Grade::Bad,
+                Gap::AppendUndetected | Gap::Unknown | Gap::Inapplicable if lenient => Grade::NotIdeal,
+                Gap::AppendUndetected | Gap::Unknown | Gap::Inapplicable => Grade::Bad,
+            },
         };
 
-        EvaluationUnit::new(grade.to_string(), self.get_deduction_data())
+        let mut data = self.get_deduction_data();
+        match grade {
+            Grade::Good => data.class = EvaluationUnitClass::Good,
+            Grade::NotIdeal if data.class == EvaluationUnitClass::Bad => data.class = EvaluationUnitClass::Neutral,
+            _ => (),
+        }
+
+        EvaluationUnit::new(Score::Grade(grade), data)
     }
 }
 
 impl CambiaEvaluator {
+    // Bumped whenever the grading changes, oldest first
+    pub const RULESETS: [&'static str; 2] = ["2024-01-01", GRADE_UNVERIFIED_NOT_IDEAL];
+    pub const RULESET: &'static str = CambiaEvaluator::RULESETS[1];
+
     pub fn new() -> Self {
-        Self
+        CambiaEvaluator { ruleset: CambiaEvaluator::RULESET }
+    }
+
+    pub fn with_ruleset(ruleset: &str) -> Result<Self, CambiaError> {
+        match CambiaEvaluator::RULESETS.iter().find(|version| **version == ruleset) {
+            Some(version) => Ok(CambiaEvaluator { ruleset: version }),
+            None => Err(CambiaError::new_anon(&format!("Unknown Cambia ruleset version: {}", ruleset))),
+        }
+    }
+
+    // The worst unit decides the grade
+    fn grade_from_units(units: &[EvaluationUnit]) -> Grade {
+        units.iter()
+            .filter_map(|unit| unit.unit_score.grade())
+            .max()
+            .unwrap_or(Grade::Good)
     }
 }
 
@@ -150,27 +216,43 @@ impl Default for CambiaEvaluator {
 impl Evaluator for CambiaEvaluator {
     fn evaluate_combined(&mut self, parsed_logs: &ParsedLogCombined) -> EvaluationCombined {
         let mut evaluations: Vec<Evaluation> = Vec::new();
+        // Re-rips of the same disc share a TOC, only the best attempt of each disc counts
+        // This can't tell apart different discs with the same TOC (i.e. vocal and instrumental discs)
+        let mut disc_grades: HashMap<String, Grade> = HashMap::new();
+
+        for (idx, parsed_log) in parsed_logs.parsed_logs.iter().enumerate() {
+            let evaluation = self.evaluate(parsed_log);
+            let grade = CambiaEvaluator::grade_from_units(&evaluation.evaluation_units);
 
-        for parsed_log in parsed_logs.parsed_logs.iter() {
-            evaluations.push(self.evaluate(parsed_log));
+            let disc_id = match parsed_log.toc.mbz.hash.is_empty() {
+                true => format!("#{}", idx),
+                false => parsed_log.toc.mbz.hash.clone(),
+            };
+            disc_grades.entry(disc_id)
+                .and_modify(|disc_grade| *disc_grade = (*disc_grade).min(grade))
+                .or_insert(grade);
+
+            evaluations.push(evaluation);
         }
 
-        // TODO: Use mode for aggregate (?) or use some other weighting
-        /*
-        TODO: A smart approach would be to pick max from a cluster of same discID
-        ^Will NOT work for releases that have different disc content with same discID (i.e. vocals and inst on same release)
-         */
-        EvaluationCombined::new(EvaluatorType::Cambia, String::from("N/A"), evaluations)
+        // The release is only as good as its worst disc
+        let combined_score = Score::worst(disc_grades.into_values().map(Score::Grade));
+
+        EvaluationCombined::new(EvaluatorType::Cambia, self.ruleset, combined_score, evaluations)
     }
 
     fn evaluate(&mut self, parsed_log: &ParsedLog) -> Evaluation {
         let mut deductions: Vec<EvaluationUnit> = Vec::new();
 
         for cambia_deduction in CambiaDeduction::iter() {
-            deductions.push(cambia_deduction.deduct(parsed_log))
+            deductions.push(cambia_deduction.deduct(parsed_log, self.ruleset))
         }
 
-        // TODO: For a score to be meaningful, worst offenders need to be distinguishable from the "worse"
-        Evaluation::new(String::from("N/A"), deductions)
+        for anomaly in parsed_log.anomalies.iter() {
+            deductions.push(EvaluationUnit::new(Score::Grade(Grade::Bad), anomaly.into()));
+        }
+
+        let grade = CambiaEvaluator::grade_from_units(&deductions);
+        Evaluation::new(Score::Grade(grade), deductions)
     }
-}
\ No newline at end of file
+}
@@ -72,6 +72,7 @@ pub enum GazelleDeductionRelease {
     Id3OnFlac,
     NotSecureCrcMismatch,
     NotSecureNoTC,
+    LogAnomalies,
 }
 
 #[derive(Serialize, Deserialize, EnumIter, Clone, Copy)]
@@ -362,6 +363,12 @@ impl GazelleDeductionData for GazelleDeductionRelease {
                 "Rip was not done in Secure mode, and T+C was not used - as a result, we cannot verify the authenticity of the rip",
                 EvaluationUnitClass::Critical
             ),
+            GazelleDeductionRelease::LogAnomalies => EvaluationUnitData::new(
+                EvaluationUnitScope::Release,
+                EvaluationUnitField::Anomaly,
+                "Log contains internal inconsistencies (possibly edited)",
+                EvaluationUnitClass::Critical
+            ),
             GazelleDeductionRelease::Id3OnFlac => EvaluationUnitData::new(
                 EvaluationUnitScope::Release,
                 EvaluationUnitField::Tag,
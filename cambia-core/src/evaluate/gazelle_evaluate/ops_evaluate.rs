@@ -1,12 +1,11 @@
 use std::{cmp::min, collections::{HashMap, HashSet}};
 
-use crate::{evaluate::{Evaluator, EvaluationCombined, EvaluationUnit, Evaluation, EvaluatorType, EvaluationUnitScope}, parser::{ParsedLogCombined, ParsedLog}, extract::{Ripper, Quartet, MediaType, ReadMode, Gap}, track::TrackEntry, integrity::Integrity, drive::{DriveUtils, DriveMatchQuality}};
+use crate::{error::CambiaError, evaluate::{Evaluator, EvaluationCombined, EvaluationUnit, Evaluation, EvaluatorType, EvaluationUnitScope, Score}, parser::{ParsedLogCombined, ParsedLog}, extract::{Ripper, Quartet, MediaType, ReadMode, Gap}, track::TrackEntry, integrity::Integrity, drive::{DriveUtils, DriveMatchQuality}};
 
 use super::{GazelleDeductionData, GazelleDeductionFail, GazelleDeductionRelease, GazelleDeductionTrack, GazelleDeduction};
 
 use regex::{Regex, RegexBuilder};
 use semver::{Version, Prerelease, BuildMetadata};
-use serde_yaml::from_str;
 use strum::IntoEnumIterator;
 use rayon::prelude::*;
 
@@ -24,17 +23,33 @@ static WHIPPER_VERSION_THRESH: Version = Version {
     build: BuildMetadata::EMPTY,
 };
 
-#[derive(Default)]
-pub struct OpsEvaluator;
+static OPS_MAX_RETRY_THRESH: u32 = 10;
+
+pub struct OpsEvaluator {
+    ruleset: &'static str,
+}
 
 impl OpsEvaluator {
+    // Dates the mirrored tracker rules took effect, oldest first
+    pub const RULESETS: [&'static str; 1] = ["2023-01-01"];
+    pub const RULESET: &'static str = OpsEvaluator::RULESETS[0];
+
     pub fn new() -> Self {
-        Self
+        OpsEvaluator { ruleset: OpsEvaluator::RULESET }
+    }
+
+    // Unregistered versions are rejected rather than scored with the current rules
+    pub fn with_ruleset(ruleset: &str) -> Result<Self, CambiaError> {
+        match OpsEvaluator::RULESETS.iter().find(|version| **version == ruleset) {
+            Some(version) => Ok(OpsEvaluator { ruleset: version }),
+            None => Err(CambiaError::new_anon(&format!("Unknown OPS ruleset version: {}", ruleset))),
+        }
     }
 
     pub fn check_fail(parsed_log: &ParsedLog, data: GazelleDeductionFail) -> bool {
         match data {
             GazelleDeductionFail::UnknownEncoding => false,
+            // The trackers only accept these three, logs from every other supported ripper (e.g. cyanrip, fre:ac) fail here
             GazelleDeductionFail::UnknownRipper => parsed_log.ripper != Ripper::EAC && parsed_log.ripper != Ripper::XLD && parsed_log.ripper != Ripper::Whipper,
             GazelleDeductionFail::WhipperVersionLowerLimit => {
                 if parsed_log.ripper != Ripper::Whipper {
@@ -92,7 +107,7 @@ impl OpsEvaluator {
             GazelleDeductionRelease::CouldNotVerifyDrive => parsed_log.drive == "Unknown Drive",
             GazelleDeductionRelease::CouldNotVerifyMedia => parsed_log.ripper == Ripper::XLD && parsed_log.ripper_version.cmp(&String::from("20130127")).is_ge() && parsed_log.media_type == MediaType::Unknown, 
             GazelleDeductionRelease::CouldNotVerifyReadMode => parsed_log.read_mode == ReadMode::Unknown,
-            GazelleDeductionRelease::CouldNotVerifyMaxRetry => false, // TODO: XLD specific prop, does not affect scoring
+            GazelleDeductionRelease::CouldNotVerifyMaxRetry => parsed_log.ripper == Ripper::XLD && parsed_log.read_mode == ReadMode::Secure && parsed_log.max_retry_count.is_none(),
             GazelleDeductionRelease::CouldNotVerifyAccurateStream => parsed_log.read_mode == ReadMode::Secure && parsed_log.accurate_stream == Quartet::Unknown,
             GazelleDeductionRelease::CouldNotVerifyDefeatAudioCache => parsed_log.read_mode == ReadMode::Secure && parsed_log.defeat_audio_cache == Quartet::Unknown,
             GazelleDeductionRelease::CouldNotVerifyC2 => parsed_log.read_mode == ReadMode::Secure && parsed_log.use_c2 == Quartet::Unknown,
@@ -103,7 +118,8 @@ impl OpsEvaluator {
             GazelleDeductionRelease::CouldNotVerifyNullSamples => parsed_log.use_null_samples == Quartet::Unknown,
             GazelleDeductionRelease::CouldNotVerifyGapHandling => parsed_log.gap_handling == Gap::Unknown,
             GazelleDeductionRelease::CouldNotVerifyId3 => parsed_log.id3_enabled == Quartet::Unknown,
-            GazelleDeductionRelease::CouldNotVerifyAlbumGain => false, // TODO: XLD specific prop, does not affect scoring
+            // Album gain is only written when ReplayGain was on, which shows in the track gains
+            GazelleDeductionRelease::CouldNotVerifyAlbumGain => parsed_log.ripper == Ripper::XLD && parsed_log.album_gain.is_none() && parsed_log.tracks.iter().any(|track| track.gain.is_some()),
             GazelleDeductionRelease::RippedWithCompressionOffset => false, // TODO: EAC specific prop, does not affect scoring
             GazelleDeductionRelease::RangeRip => {
                 if parsed_log.ripper != Ripper::EAC {
@@ -128,7 +144,7 @@ impl OpsEvaluator {
             // They don't account for XLD not being secure
             GazelleDeductionRelease::RipModeNotSecure => parsed_log.ripper == Ripper::EAC && parsed_log.read_mode != ReadMode::Secure,
             GazelleDeductionRelease::NotPressedCd => parsed_log.ripper != Ripper::EAC && parsed_log.media_type != MediaType::Pressed,
-            GazelleDeductionRelease::LowMaxRetryCount => false, // TODO: XLD specific prop, does not affect scoring
+            GazelleDeductionRelease::LowMaxRetryCount => parsed_log.ripper == Ripper::XLD && parsed_log.max_retry_count.is_some_and(|count| count < OPS_MAX_RETRY_THRESH),
             GazelleDeductionRelease::AccurateStreamNotUtilized => parsed_log.accurate_stream == Quartet::False,
             GazelleDeductionRelease::UsedC2 => parsed_log.use_c2 == Quartet::True,
             GazelleDeductionRelease::DoesNotFillMissingOffsetSamples => parsed_log.fill_silence == Quartet::False,
@@ -154,6 +170,7 @@ impl OpsEvaluator {
 
                 parsed_log.test_and_copy != Quartet::True && parsed_log.tracks.iter().all(|t| !t.aborted)
             },
+            GazelleDeductionRelease::LogAnomalies => !parsed_log.anomalies.is_empty(),
         }
     }
 
@@ -202,6 +219,75 @@ impl OpsEvaluator {
             GazelleDeductionTrack::InconsistenciesInErrorSectors(_) => parsed_log.ripper == Ripper::XLD && track_entry.errors.inconsistent_err_sectors.count > 0,
         }
     }
+
+    // RED shares every deduction and weight with OPS, only the fail checks differ
+    pub(crate) fn evaluate_with(parsed_log: &ParsedLog, check_fail: fn(&ParsedLog, GazelleDeductionFail) -> bool) -> Evaluation {
+        let mut score: Score = Score::Numeric(100);
+        let mut deductions: Vec<EvaluationUnit> = Vec::new();
+
+        for gazelle_deduction_fail in GazelleDeductionFail::iter() {
+            if check_fail(parsed_log, gazelle_deduction_fail) {
+                let deduction = gazelle_deduction_fail.deduct(parsed_log);
+                score = score - deduction.unit_score;
+                deductions.push(deduction);
+                return Evaluation::new_failed(score, deductions);
+            }
+        }
+
+        let mut deductions_release: Vec<EvaluationUnit> = GazelleDeductionRelease::iter()
+            .par_bridge()
+            .filter_map(|gazelle_deduction_release| {
+                if OpsEvaluator::check_release(parsed_log, gazelle_deduction_release) {
+                    let deduction = gazelle_deduction_release.deduct(parsed_log);
+                    Some(deduction)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let score_release: Score = deductions_release
+            .iter()
+            .map(|deduction| deduction.unit_score)
+            .sum();
+
+        deductions.append(&mut deductions_release);
+        score = score - score_release;
+
+        let mut deductions_track: Vec<_> = parsed_log
+            .tracks
+            .par_iter()
+            .flat_map(|track| {
+                GazelleDeductionTrack::iter()
+                    .filter_map(|gazelle_deduction_track| {
+                        let gazelle_deduction_track_variant: GazelleDeductionTrack = match gazelle_deduction_track {
+                            GazelleDeductionTrack::ReadErrors(_) => GazelleDeductionTrack::ReadErrors(track.errors.read.count),
+                            GazelleDeductionTrack::SkippedErrors(_) => GazelleDeductionTrack::SkippedErrors(track.errors.skip.count),
+                            GazelleDeductionTrack::DamagedSectors(_) => GazelleDeductionTrack::DamagedSectors(track.errors.damaged_sectors.count),
+                            GazelleDeductionTrack::InconsistenciesInErrorSectors(_) => GazelleDeductionTrack::InconsistenciesInErrorSectors(track.errors.inconsistent_err_sectors.count),
+                            other => other,
+                        };
+                        if OpsEvaluator::check_track(parsed_log, track, gazelle_deduction_track_variant) {
+                            let mut deduction = gazelle_deduction_track_variant.deduct(parsed_log);
+                            deduction.data.scope = EvaluationUnitScope::Track(Some(track.num)); // TODO: Special considerations for HTOA (?)
+                            Some(deduction)
+                        } else {
+                            None
+                        }
+                    })
+                    .collect::<Vec<_>>()
+            })
+            .collect();
+
+        let score_track: Score = deductions_track
+            .iter()
+            .map(|deduction| deduction.unit_score)
+            .sum();
+
+        deductions.append(&mut deductions_track);
+        score = score - score_track;
+        
+        Evaluation::new(score, deductions)
+    }
 }
 
 impl GazelleDeduction for GazelleDeductionFail {
@@ -230,6 +316,7 @@ impl GazelleDeduction for GazelleDeductionRelease {
             GazelleDeductionRelease::CouldNotVerifyDrive => 1,
             GazelleDeductionRelease::CouldNotVerifyMedia => 1,
             GazelleDeductionRelease::CouldNotVerifyReadMode => 1,
+            // The OPS logchecker lists the retry count and gain findings without deducting points
             GazelleDeductionRelease::CouldNotVerifyMaxRetry => 0,
             GazelleDeductionRelease::CouldNotVerifyAccurateStream => 20,
             GazelleDeductionRelease::CouldNotVerifyDefeatAudioCache => 1,
@@ -258,6 +345,8 @@ impl GazelleDeduction for GazelleDeductionRelease {
             GazelleDeductionRelease::Id3OnFlac => 1,
             GazelleDeductionRelease::NotSecureCrcMismatch => 20,
             GazelleDeductionRelease::NotSecureNoTC => 40,
+            // Not part of the OPS logchecker, surfaced without affecting the score
+            GazelleDeductionRelease::LogAnomalies => 0,
         };
         EvaluationUnit::new_from_u32(deduction_score, self.get_deduction_data())
     }
@@ -293,6 +382,12 @@ impl GazelleDeduction for GazelleDeductionTrack {
     }
 }
 
+impl Default for OpsEvaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl Evaluator for OpsEvaluator {
     fn evaluate_combined(&mut self, plc: &ParsedLogCombined) -> EvaluationCombined {
         let mut evaluations: Vec<Evaluation> = Vec::new();
@@ -351,84 +446,20 @@ impl Evaluator for OpsEvaluator {
         }
 
         // Deduction aggregates
-        let release_deduction_score: i32 = release_deduction_set
-                                            .into_iter()
-                                            .map(|x| x.unit_score.parse::<i32>().unwrap())
+        let release_deduction_score: Score = release_deduction_set
+                                            .iter()
+                                            .map(|x| x.unit_score)
                                             .sum();
-        let track_deduction_score: i32 = track_deduction_map
+        let track_deduction_score: Score = track_deduction_map
                                             .values()
-                                            .flat_map(|ds| ds.iter().map(|d| d.unit_score.parse::<i32>().unwrap_or_default()))
+                                            .flat_map(|ds| ds.iter().map(|d| d.unit_score))
                                             .sum();
         
-        let combined_score: i32 = 100 - release_deduction_score - track_deduction_score;
-        EvaluationCombined::new(EvaluatorType::OPS, combined_score.to_string(), evaluations)
+        let combined_score: Score = Score::Numeric(100) - release_deduction_score - track_deduction_score;
+        EvaluationCombined::new(EvaluatorType::OPS, self.ruleset, combined_score, evaluations)
     }
 
     fn evaluate(&mut self, parsed_log: &ParsedLog) -> Evaluation {
-        let mut score: i32 = 100_i32;
-        let mut deductions: Vec<EvaluationUnit> = Vec::new();
-
-        for gazelle_deduction_fail in GazelleDeductionFail::iter() {
-            if OpsEvaluator::check_fail(parsed_log, gazelle_deduction_fail) {
-                let deduction = gazelle_deduction_fail.deduct(parsed_log);
-                score -= from_str::<i32>(deduction.unit_score.as_str()).unwrap();
-                deductions.push(deduction);
-                return Evaluation::new(score.to_string(), deductions);
-            }
-        }
-
-        let mut deductions_release: Vec<EvaluationUnit> = GazelleDeductionRelease::iter()
-            .par_bridge()
-            .filter_map(|gazelle_deduction_release| {
-                if OpsEvaluator::check_release(parsed_log, gazelle_deduction_release) {
-                    let deduction = gazelle_deduction_release.deduct(parsed_log);
-                    Some(deduction)
-                } else {
-                    None
-                }
-            })
-            .collect();
-        let score_release: i32 = deductions_release
-            .iter()
-            .map(|deduction| from_str::<i32>(deduction.unit_score.as_str()).unwrap())
-            .sum();
-
-        deductions.append(&mut deductions_release);
-        score -= score_release;
-
-        let mut deductions_track: Vec<_> = parsed_log
-            .tracks
-            .par_iter()
-            .flat_map(|track| {
-                GazelleDeductionTrack::iter()
-                    .filter_map(|gazelle_deduction_track| {
-                        let gazelle_deduction_track_variant: GazelleDeductionTrack = match gazelle_deduction_track {
-                            GazelleDeductionTrack::ReadErrors(_) => GazelleDeductionTrack::ReadErrors(track.errors.read.count),
-                            GazelleDeductionTrack::SkippedErrors(_) => GazelleDeductionTrack::SkippedErrors(track.errors.skip.count),
-                            GazelleDeductionTrack::DamagedSectors(_) => GazelleDeductionTrack::DamagedSectors(track.errors.damaged_sectors.count),
-                            GazelleDeductionTrack::InconsistenciesInErrorSectors(_) => GazelleDeductionTrack::InconsistenciesInErrorSectors(track.errors.inconsistent_err_sectors.count),
-                            other => other,
-                        };
-                        if OpsEvaluator::check_track(parsed_log, track, gazelle_deduction_track_variant) {
-                            let mut deduction = gazelle_deduction_track_variant.deduct(parsed_log);
-                            deduction.data.scope = EvaluationUnitScope::Track(Some(track.num)); // TODO: Special considerations for HTOA (?)
-                            Some(deduction)
-                        } else {
-                            None
-                        }
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect();
-
-        let score_track: i32 = deductions_track
-            .iter()
-            .map(|deduction| from_str::<i32>(deduction.unit_score.as_str()).unwrap())
-            .sum();
-
-        deductions.append(&mut deductions_track);
-        score -=score_track;
-        
-        Evaluation::new(score.to_string(), deductions)
+        OpsEvaluator::evaluate_with(parsed_log, OpsEvaluator::check_fail)
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,52 @@
+use crate::{error::CambiaError, evaluate::{Evaluator, EvaluationCombined, Evaluation, EvaluatorType, Score}, parser::{ParsedLogCombined, ParsedLog}, extract::Ripper};
+
+use super::{ops_evaluate::OpsEvaluator, GazelleDeductionFail};
+
+// RED's checker is the upstream of the OPS one, it differs in the following:
+// - Only EAC and XLD logs are accepted, whipper logs are unknown
+// - Logs in an upload are scored one by one, the upload gets the lowest score
+// There is no dated RED checker revision to mirror, so RED has no ruleset of its own and reports the OPS one
+pub struct RedEvaluator {
+    ruleset: &'static str,
+}
+
+impl RedEvaluator {
+    pub fn new() -> Self {
+        RedEvaluator { ruleset: OpsEvaluator::RULESET }
+    }
+
+    pub fn with_ruleset(ruleset: &str) -> Result<Self, CambiaError> {
+        match OpsEvaluator::RULESETS.iter().find(|version| **version == ruleset) {
+            Some(version) => Ok(RedEvaluator { ruleset: version }),
+            None => Err(CambiaError::new_anon(&format!("Unknown OPS ruleset version: {}", ruleset))),
+        }
+    }
+
+    // whipper is rejected as an unknown ripper before its version is ever looked at
+    pub fn check_fail(parsed_log: &ParsedLog, data: GazelleDeductionFail) -> bool {
+        match data {
+            GazelleDeductionFail::UnknownRipper => parsed_log.ripper != Ripper::EAC && parsed_log.ripper != Ripper::XLD,
+            other => OpsEvaluator::check_fail(parsed_log, other),
+        }
+    }
+}
+
+impl Default for RedEvaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Evaluator for RedEvaluator {
+    fn evaluate_combined(&mut self, plc: &ParsedLogCombined) -> EvaluationCombined {
+        let evaluations: Vec<Evaluation> = plc.parsed_logs.iter().map(|log| self.evaluate(log)).collect();
+
+        let combined_score = Score::worst(evaluations.iter().map(|evaluation| evaluation.score));
+
+        EvaluationCombined::new(EvaluatorType::RED, self.ruleset, combined_score, evaluations)
+    }
+
+    fn evaluate(&mut self, parsed_log: &ParsedLog) -> Evaluation {
+        OpsEvaluator::evaluate_with(parsed_log, RedEvaluator::check_fail)
+    }
+}
@@ -0,0 +1,168 @@
+use std::fmt;
+
+use serde::{Serialize, Deserialize};
+use ts_rs::TS;
+
+use crate::error::CambiaError;
+
+use super::Evaluator;
+
+struct RegistryEntry {
+    name: &'static str,
+    description: &'static str,
+    // Runs when the caller doesn't pick any evaluators
+    default: bool,
+    // Ruleset versions oldest first, the last one is the latest
+    versions: fn() -> Vec<&'static str>,
+    build: fn(&'static str) -> Box<dyn Evaluator>,
+}
+
+#[derive(Serialize, Deserialize, TS)]
+#[ts(export)]
+pub struct EvaluatorInfo {
+    pub name: String,
+    pub description: String,
+    pub default: bool,
+    pub versions: Vec<String>,
+}
+
+// Either the latest ruleset or the one in effect on a date
+#[derive(Clone, PartialEq, Eq, Debug)]
+pub enum RulesetVersion {
+    Latest,
+    Date(String),
+}
+
+impl RulesetVersion {
+    // `latest` or a YYYY-MM-DD date
+    pub fn parse(version: &str) -> Result<Self, CambiaError> {
+        let version = version.trim();
+        if version.is_empty() || version.eq_ignore_ascii_case("latest") {
+            return Ok(RulesetVersion::Latest);
+        }
+
+        let is_date = version.len() == 10 && version.char_indices().all(|(idx, c)| match idx {
+            4 | 7 => c == '-',
+            _ => c.is_ascii_digit(),
+        });
+        if !is_date {
+            return Err(CambiaError::new_anon(&format!("Invalid ruleset version: {}. Expected a YYYY-MM-DD date or latest.", version)));
+        }
+
+        Ok(RulesetVersion::Date(version.to_owned()))
+    }
+
+    // Dates pick the newest version that had taken effect by then
+    pub fn resolve(&self, versions: &[&'static str]) -> Option<&'static str> {
+        match self {
+            RulesetVersion::Latest => versions.last().copied(),
+            RulesetVersion::Date(date) => versions.iter().rev().find(|version| **version <= date.as_str()).copied(),
+        }
+    }
+}
+
+impl fmt::Display for RulesetVersion {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            RulesetVersion::Latest => write!(f, "latest"),
+            RulesetVersion::Date(date) => write!(f, "{}", date),
+        }
+    }
+}
+
+fn entries() -> Vec<RegistryEntry> {
+    vec![
+        #[cfg(feature = "ops_ev")]
+        RegistryEntry {
+            name: "ops",
+            description: "OPS log checker",
+            default: true,
+            versions: || crate::evaluate::gazelle_evaluate::ops_evaluate::OpsEvaluator::RULESETS.to_vec(),
+            build: |version| Box::new(crate::evaluate::gazelle_evaluate::ops_evaluate::OpsEvaluator::with_ruleset(version).expect("Unregistered OPS ruleset")),
+        },
+        #[cfg(feature = "red_ev")]
+        RegistryEntry {
+            name: "red",
+            description: "RED log checker, scored with the OPS rulesets",
+            default: true,
+            versions: || crate::evaluate::gazelle_evaluate::ops_evaluate::OpsEvaluator::RULESETS.to_vec(),
+            build: |version| Box::new(crate::evaluate::gazelle_evaluate::red_evaluate::RedEvaluator::with_ruleset(version).expect("Unregistered OPS ruleset")),
+        },
+        #[cfg(feature = "cambia_ev")]
+        RegistryEntry {
+            name: "cambia",
+            description: "Cambia grading",
+            default: true,
+            versions: || crate::evaluate::cambia_evaluate::CambiaEvaluator::RULESETS.to_vec(),
+            build: |version| Box::new(crate::evaluate::cambia_evaluate::CambiaEvaluator::with_ruleset(version).expect("Unregistered Cambia ruleset")),
+        },
+        #[cfg(feature = "rules_ev")]
+        RegistryEntry {
+            name: "ops_rules",
+            description: "OPS log checker from the bundled rule file",
+            default: false,
+            versions: crate::evaluate::rule_evaluate::RuleSet::ops_versions,
+            build: |version| Box::new(crate::evaluate::rule_evaluate::RuleEvaluator::new(
+                crate::evaluate::rule_evaluate::RuleSet::ops_version(version).expect("Bundled OPS rules are invalid"),
+            )),
+        },
+    ]
+}
+
+pub fn available() -> Vec<EvaluatorInfo> {
+    entries()
+        .into_iter()
+        .map(|entry| EvaluatorInfo {
+            name: entry.name.to_owned(),
+            description: entry.description.to_owned(),
+            default: entry.default,
+            versions: (entry.versions)().into_iter().map(String::from).collect(),
+        })
+        .collect()
+}
+
+// Comma separated names with an optional ruleset version, i.e. `ops,red@2023-01-01,ops_rules@latest`
+pub fn parse_names(list: &str) -> Vec<String> {
+    list.split(',')
+        .map(|name| name.trim().to_ascii_lowercase())
+        .filter(|name| !name.is_empty())
+        .collect()
+}
+
+// No names means the default set at their latest rulesets, unknown names or versions are an error
+pub fn select(names: &[String]) -> Result<Vec<Box<dyn Evaluator>>, CambiaError> {
+    let entries = entries();
+
+    if names.is_empty() {
+        return Ok(entries.iter()
+            .filter(|entry| entry.default)
+            .filter_map(|entry| RulesetVersion::Latest.resolve(&(entry.versions)()).map(entry.build))
+            .collect());
+    }
+
+    let mut selected: Vec<(&RegistryEntry, &'static str)> = Vec::new();
+    for name in names {
+        let name = name.trim().to_ascii_lowercase();
+        let (name, version) = match name.split_once('@') {
+            Some((name, version)) => (name, RulesetVersion::parse(version)?),
+            None => (name.as_str(), RulesetVersion::Latest),
+        };
+
+        let Some(entry) = entries.iter().find(|entry| entry.name == name) else {
+            let known = entries.iter().map(|entry| entry.name).collect::<Vec<&str>>().join(", ");
+            return Err(CambiaError::new_anon(&format!("Unknown evaluator: {}. Available evaluators: {}.", name, known)));
+        };
+
+        let versions = (entry.versions)();
+        let Some(resolved) = version.resolve(&versions) else {
+            return Err(CambiaError::new_anon(&format!("No {} ruleset was in effect on {}. Available versions: {}.", name, version, versions.join(", "))));
+        };
+
+        // The same evaluator can run with several rulesets side by side
+        if !selected.iter().any(|(s, v)| s.name == entry.name && *v == resolved) {
+            selected.push((entry, resolved));
+        }
+    }
+
+    Ok(selected.into_iter().map(|(entry, version)| (entry.build)(version)).collect())
+}
@@ -0,0 +1,475 @@
+use std::{cmp::{min, Ordering}, collections::{HashMap, HashSet}, fs, path::Path};
+
+use regex::Regex;
+use semver::Version;
+use serde::{de, Deserialize, Deserializer};
+use serde_json::{json, Value};
+
+use crate::{drive::{DriveMatchQuality, DriveUtils}, error::CambiaError, extract::Ripper, parser::{ParsedLog, ParsedLogCombined}};
+
+use super::{Evaluation, EvaluationCombined, EvaluationUnit, EvaluationUnitClass, EvaluationUnitData, EvaluationUnitField, EvaluationUnitScope, Evaluator, EvaluatorType, Score};
+
+// Oldest first, each file holds the rules that took effect on its date
+static OPS_RULES: [(&str, &str); 1] = [
+    ("2023-01-01", include_str!("../../rules/ops/2023-01-01.yaml")),
+];
+static NULL: Value = Value::Null;
+
+lazy_static! {
+    static ref VERSION: Regex = Regex::new(r"(?P<major>\d+)\.(?P<minor>\d+)\.(?P<patch>\d+)").unwrap();
+}
+
+// Fields are addressed by their serialized names (the same as in the JSON output), separated by dots
+// Array elements are addressed by index, e.g. `track.filenames.0`
+// On top of the ParsedLog fields, `drive_db.match` (Strong/Weak) and `drive_db.offset` hold the drive DB lookup
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct RuleSet {
+    pub name: String,
+    // Date the rules took effect (YYYY-MM-DD)
+    pub version: String,
+    #[serde(default = "RuleSet::default_evaluator")]
+    pub evaluator: EvaluatorType,
+    #[serde(default = "RuleSet::default_base_score")]
+    pub base_score: i32,
+    #[serde(default)]
+    pub aggregate: Aggregate,
+    // The first matching fail rule ends the evaluation of a log
+    #[serde(default)]
+    pub fail: Vec<Rule>,
+    #[serde(default)]
+    pub release: Vec<Rule>,
+    // Checked once per track, the current track is bound to `track`
+    #[serde(default)]
+    pub track: Vec<Rule>,
+}
+
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Aggregate {
+    // Release deductions are deduplicated across logs, re-ripped tracks replace earlier deductions
+    #[default]
+    Gazelle,
+    // The worst log decides
+    Minimum,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub id: String,
+    pub when: Condition,
+    pub field: EvaluationUnitField,
+    pub class: EvaluationUnitClass,
+    pub message: String,
+    pub score: RuleScore,
+    #[serde(default)]
+    pub combined: Combined,
+}
+
+// How a deduction is treated when several logs are evaluated together
+#[derive(Deserialize, Default, Clone, Copy)]
+#[serde(rename_all = "snake_case")]
+pub enum Combined {
+    #[default]
+    Keep,
+    // Only counts for single logs
+    Drop,
+    // Only counts if the last log has it
+    LastLog,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum RuleScore {
+    Fixed(i32),
+    Field {
+        field: String,
+        #[serde(default)]
+        max: Option<i32>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Condition {
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+    Eq(Comparison),
+    Ne(Comparison),
+    Lt(Comparison),
+    Le(Comparison),
+    Gt(Comparison),
+    Ge(Comparison),
+    In {
+        field: String,
+        values: Vec<Value>,
+    },
+    // Present and not null
+    Set(String),
+    // Missing, null, or an empty string/list
+    Empty(String),
+    Matches {
+        field: String,
+        pattern: Pattern,
+    },
+    // Unparseable versions count as below
+    VersionBelow {
+        field: String,
+        value: Threshold,
+    },
+    // Quantifiers over a list, the current element is bound to `item`
+    #[serde(rename = "some")]
+    AnyItem {
+        list: String,
+        #[serde(rename = "where")]
+        condition: Box<Condition>,
+    },
+    #[serde(rename = "every")]
+    EveryItem {
+        list: String,
+        #[serde(rename = "where")]
+        condition: Box<Condition>,
+    },
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Comparison {
+    pub field: String,
+    pub value: Operand,
+    // Compare the length of the field instead of its value
+    #[serde(default)]
+    pub length: bool,
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum Operand {
+    Field { field: String },
+    Literal(Value),
+}
+
+pub struct Pattern(Regex);
+
+impl<'de> Deserialize<'de> for Pattern {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let pattern = String::deserialize(deserializer)?;
+        Regex::new(&pattern).map(Pattern).map_err(de::Error::custom)
+    }
+}
+
+pub struct Threshold(Version);
+
+impl<'de> Deserialize<'de> for Threshold {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let version = String::deserialize(deserializer)?;
+        parse_version(&version).map(Threshold).ok_or_else(|| de::Error::custom(format!("invalid version: {}", version)))
+    }
+}
+
+struct Context<'a> {
+    log: &'a Value,
+    bindings: Vec<(&'static str, &'a Value)>,
+}
+
+impl<'a> Context<'a> {
+    fn new(log: &'a Value) -> Self {
+        Context { log, bindings: Vec::new() }
+    }
+
+    fn bind(&self, name: &'static str, value: &'a Value) -> Context<'a> {
+        let mut bindings = self.bindings.clone();
+        bindings.push((name, value));
+        Context { log: self.log, bindings }
+    }
+
+    fn resolve(&self, path: &str) -> Option<&'a Value> {
+        let mut segments = path.split('.');
+        let first = segments.next()?;
+        let root = match self.bindings.iter().rev().find(|(name, _)| *name == first) {
+            Some((_, value)) => *value,
+            None => self.log.get(first)?,
+        };
+
+        segments.try_fold(root, |value, segment| match value {
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => value.get(segment),
+        })
+    }
+
+    fn items(&self, path: &str) -> impl Iterator<Item = &'a Value> {
+        self.resolve(path).and_then(Value::as_array).into_iter().flatten()
+    }
+}
+
+impl Condition {
+    fn check(&self, context: &Context) -> bool {
+        match self {
+            Condition::All(conditions) => conditions.iter().all(|condition| condition.check(context)),
+            Condition::Any(conditions) => conditions.iter().any(|condition| condition.check(context)),
+            Condition::Not(condition) => !condition.check(context),
+            Condition::Eq(comparison) => comparison.ordering(context) == Some(Ordering::Equal),
+            Condition::Ne(comparison) => comparison.ordering(context) != Some(Ordering::Equal),
+            Condition::Lt(comparison) => comparison.ordering(context) == Some(Ordering::Less),
+            Condition::Le(comparison) => matches!(comparison.ordering(context), Some(Ordering::Less | Ordering::Equal)),
+            Condition::Gt(comparison) => comparison.ordering(context) == Some(Ordering::Greater),
+            Condition::Ge(comparison) => matches!(comparison.ordering(context), Some(Ordering::Greater | Ordering::Equal)),
+            Condition::In { field, values } => {
+                let value = context.resolve(field).unwrap_or(&NULL);
+                values.iter().any(|candidate| compare(value, candidate) == Some(Ordering::Equal))
+            },
+            Condition::Set(field) => context.resolve(field).is_some_and(|value| !value.is_null()),
+            Condition::Empty(field) => context.resolve(field).is_none_or(is_empty),
+            Condition::Matches { field, pattern } => context.resolve(field).and_then(Value::as_str).is_some_and(|value| pattern.0.is_match(value)),
+            Condition::VersionBelow { field, value } => match context.resolve(field).and_then(Value::as_str).and_then(parse_version) {
+                Some(version) => version < value.0,
+                None => true,
+            },
+            Condition::AnyItem { list, condition } => context.items(list).any(|item| condition.check(&context.bind("item", item))),
+            Condition::EveryItem { list, condition } => context.items(list).all(|item| condition.check(&context.bind("item", item))),
+        }
+    }
+}
+
+impl Comparison {
+    fn ordering(&self, context: &Context) -> Option<Ordering> {
+        let mut left = context.resolve(&self.field).cloned().unwrap_or(Value::Null);
+        if self.length {
+            left = value_len(&left).map_or(Value::Null, Value::from);
+        }
+
+        let right = match &self.value {
+            Operand::Field { field } => context.resolve(field).unwrap_or(&NULL),
+            Operand::Literal(value) => value,
+        };
+
+        compare(&left, right)
+    }
+}
+
+impl RuleScore {
+    fn value(&self, context: &Context) -> i32 {
+        match self {
+            RuleScore::Fixed(score) => *score,
+            RuleScore::Field { field, max } => {
+                let score = context.resolve(field)
+                    .and_then(Value::as_i64)
+                    .and_then(|score| i32::try_from(score).ok())
+                    .unwrap_or_default();
+                max.map_or(score, |max| min(score, max))
+            },
+        }
+    }
+}
+
+impl Rule {
+    fn deduct(&self, context: &Context, scope: EvaluationUnitScope) -> EvaluationUnit {
+        tracing::trace!("Rule {} matched", self.id);
+        EvaluationUnit::new(
+            Score::Numeric(self.score.value(context)),
+            EvaluationUnitData::new(scope, self.field.clone(), &self.message, self.class.clone()),
+        )
+    }
+}
+
+impl RuleSet {
+    fn default_evaluator() -> EvaluatorType {
+        EvaluatorType::Custom
+    }
+
+    fn default_base_score() -> i32 {
+        100
+    }
+
+    // serde_yaml only reads enums from tags, going through JSON lets conditions be written as single key maps
+    pub fn from_yaml(yaml: &str) -> Result<Self, CambiaError> {
+        let value: Value = serde_yaml::from_str(yaml).map_err(|e| CambiaError::new_anon(&format!("Invalid rule file: {}", e)))?;
+        serde_json::from_value(value).map_err(|e| CambiaError::new_anon(&format!("Invalid rule file: {}", e)))
+    }
+
+    pub fn from_file(path: &Path) -> Result<Self, CambiaError> {
+        let yaml = fs::read_to_string(path).map_err(|e| CambiaError::new_anon(&format!("Could not read rule file {}: {}", path.display(), e)))?;
+        RuleSet::from_yaml(&yaml)
+    }
+
+    pub fn ops_versions() -> Vec<&'static str> {
+        OPS_RULES.iter().map(|(version, _)| *version).collect()
+    }
+
+    // The latest version mirrors the hard-coded OPS evaluator
+    pub fn ops() -> Self {
+        let (_, rules) = OPS_RULES.last().unwrap();
+        RuleSet::from_yaml(rules).expect("Bundled OPS rules are invalid")
+    }
+
+    pub fn ops_version(version: &str) -> Result<Self, CambiaError> {
+        match OPS_RULES.iter().find(|(v, _)| *v == version) {
+            Some((_, rules)) => RuleSet::from_yaml(rules),
+            None => Err(CambiaError::new_anon(&format!("Unknown OPS ruleset version: {}", version))),
+        }
+    }
+}
+
+pub struct RuleEvaluator {
+    rules: RuleSet,
+}
+
+impl RuleEvaluator {
+    pub fn new(rules: RuleSet) -> Self {
+        RuleEvaluator { rules }
+    }
+
+    pub fn ops() -> Self {
+        RuleEvaluator::new(RuleSet::ops())
+    }
+
+    fn log_value(parsed_log: &ParsedLog) -> Value {
+        let mut log = serde_json::to_value(parsed_log).unwrap_or(Value::Null);
+
+        let (quality, offset) = match DriveUtils::fuzzy_search_model(parsed_log.drive.clone()) {
+            DriveMatchQuality::STRONG(offset) => ("Strong", offset),
+            DriveMatchQuality::WEAK(offset) => ("Weak", offset),
+        };
+        if let Value::Object(fields) = &mut log {
+            fields.insert(String::from("drive_db"), json!({ "match": quality, "offset": offset }));
+        }
+
+        log
+    }
+
+    // Every unit comes with the combined handling of the rule that produced it
+    fn evaluate_tagged(&self, parsed_log: &ParsedLog) -> (Evaluation, Vec<Combined>) {
+        let log = RuleEvaluator::log_value(parsed_log);
+        let context = Context::new(&log);
+        let mut deductions: Vec<EvaluationUnit> = Vec::new();
+        let mut combined: Vec<Combined> = Vec::new();
+
+        if let Some(rule) = self.rules.fail.iter().find(|rule| rule.when.check(&context)) {
+            let deduction = rule.deduct(&context, EvaluationUnitScope::Release);
+            let score = Score::Numeric(self.rules.base_score) - deduction.unit_score;
+            return (Evaluation::new_failed(score, vec![deduction]), vec![rule.combined]);
+        }
+
+        for rule in self.rules.release.iter().filter(|rule| rule.when.check(&context)) {
+            deductions.push(rule.deduct(&context, EvaluationUnitScope::Release));
+            combined.push(rule.combined);
+        }
+
+        for track in context.items("tracks") {
+            let num = track.get("num").and_then(Value::as_u64).and_then(|num| u8::try_from(num).ok());
+            let track_context = context.bind("track", track);
+            for rule in self.rules.track.iter().filter(|rule| rule.when.check(&track_context)) {
+                deductions.push(rule.deduct(&track_context, EvaluationUnitScope::Track(num)));
+                combined.push(rule.combined);
+            }
+        }
+
+        let score = Score::Numeric(self.rules.base_score) - deductions.iter().map(|deduction| deduction.unit_score).sum::<Score>();
+        (Evaluation::new(score, deductions), combined)
+    }
+
+    // Same aggregation as the OPS evaluator
+    fn aggregate_gazelle(&self, parsed_logs: &[ParsedLog], evaluated: &[(Evaluation, Vec<Combined>)]) -> Score {
+        let mut release_deductions: HashSet<&EvaluationUnit> = HashSet::new();
+        let mut track_deductions: HashMap<usize, Vec<&EvaluationUnit>> = HashMap::new();
+
+        for (idx, (log, (evaluation, combined))) in parsed_logs.iter().zip(evaluated.iter()).enumerate() {
+            let mut log_track_deductions: HashMap<usize, Vec<&EvaluationUnit>> = HashMap::new();
+
+            for (deduction, handling) in evaluation.evaluation_units.iter().zip(combined.iter()) {
+                let counts = match handling {
+                    Combined::Keep => true,
+                    Combined::Drop => parsed_logs.len() == 1,
+                    Combined::LastLog => idx + 1 == parsed_logs.len(),
+                };
+                if !counts {
+                    continue;
+                }
+
+                match deduction.data.scope {
+                    EvaluationUnitScope::Release => {
+                        release_deductions.insert(deduction);
+                    },
+                    EvaluationUnitScope::Track(t) => {
+                        log_track_deductions
+                            .entry(t.unwrap_or_default() as usize)
+                            .or_default()
+                            .push(deduction);
+                    },
+                }
+            }
+
+            // Re-ripped tracks overwrite the deductions of earlier logs
+            let (start_track, total_tracks): (usize, usize) = match (log.toc.raw.entries.is_empty(), log.tracks.first()) {
+                (_, None) => (1, 0),
+                (_, Some(first)) if first.is_range && log.ripper == Ripper::EAC => (0, 0),
+                (false, Some(_)) => (1, log.toc.raw.entries.len()),
+                (true, Some(_)) => (1, log.tracks.last().unwrap().num as usize),
+            };
+            let tracks_ripped = log.tracks.iter().map(|t| t.num as usize).collect::<HashSet<_>>();
+            for t in start_track..=total_tracks {
+                if tracks_ripped.contains(&t) {
+                    track_deductions.insert(t, log_track_deductions.remove(&t).unwrap_or_default());
+                }
+            }
+        }
+
+        Score::Numeric(self.rules.base_score)
+            - release_deductions.iter().map(|deduction| deduction.unit_score).sum::<Score>()
+            - track_deductions.values().flatten().map(|deduction| deduction.unit_score).sum::<Score>()
+    }
+}
+
+impl Evaluator for RuleEvaluator {
+    fn evaluate_combined(&mut self, parsed_logs: &ParsedLogCombined) -> EvaluationCombined {
+        let evaluated: Vec<(Evaluation, Vec<Combined>)> = parsed_logs.parsed_logs.iter()
+            .map(|parsed_log| self.evaluate_tagged(parsed_log))
+            .collect();
+
+        let combined_score = match self.rules.aggregate {
+            Aggregate::Gazelle => self.aggregate_gazelle(&parsed_logs.parsed_logs, &evaluated),
+            Aggregate::Minimum => Score::worst(evaluated.iter().map(|(evaluation, _)| evaluation.score)),
+        };
+
+        let evaluations = evaluated.into_iter().map(|(evaluation, _)| evaluation).collect();
+        EvaluationCombined::new(self.rules.evaluator, &self.rules.version, combined_score, evaluations)
+    }
+
+    fn evaluate(&mut self, parsed_log: &ParsedLog) -> Evaluation {
+        self.evaluate_tagged(parsed_log).0
+    }
+}
+
+fn compare(left: &Value, right: &Value) -> Option<Ordering> {
+    match (left, right) {
+        (Value::Number(l), Value::Number(r)) => l.as_f64()?.partial_cmp(&r.as_f64()?),
+        (Value::String(l), Value::String(r)) => Some(l.cmp(r)),
+        (Value::Bool(l), Value::Bool(r)) => Some(l.cmp(r)),
+        (Value::Null, Value::Null) => Some(Ordering::Equal),
+        _ => None,
+    }
+}
+
+fn value_len(value: &Value) -> Option<usize> {
+    match value {
+        Value::String(s) => Some(s.len()),
+        Value::Array(items) => Some(items.len()),
+        Value::Object(fields) => Some(fields.len()),
+        _ => None,
+    }
+}
+
+fn is_empty(value: &Value) -> bool {
+    value_len(value).map_or(value.is_null(), |len| len == 0)
+}
+
+fn parse_version(version: &str) -> Option<Version> {
+    let c = VERSION.captures(version)?;
+    Some(Version::new(
+        c.name("major")?.as_str().parse().ok()?,
+        c.name("minor")?.as_str().parse().ok()?,
+        c.name("patch")?.as_str().parse().ok()?,
+    ))
+}
@@ -1,9 +1,11 @@
+use std::fmt;
+
 use serde::{Serialize, Deserialize};
 use ts_rs::TS;
 
-use crate::{toc::Toc, track::{AccurateRipUnit, TestAndCopy, TrackEntry, TrackError}, util::Time};
+use crate::{anomaly::Anomaly, ctdb::Ctdb, toc::Toc, track::{AccurateRipUnit, TestAndCopy, TrackEntry, TrackError}, util::Time};
 
-#[derive(Serialize, Deserialize, PartialEq, TS)]
+#[derive(Serialize, Deserialize, PartialEq, TS, Clone, Copy, Debug)]
 #[ts(export)]
 pub enum Ripper {
     #[serde(rename = "Exact Audio Copy")]
@@ -28,6 +30,25 @@ pub enum Ripper {
     Other,
 }
 
+impl fmt::Display for Ripper {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let name = match self {
+            Ripper::EAC => "Exact Audio Copy",
+            Ripper::XLD => "X Lossless Decoder",
+            Ripper::Whipper => "whipper",
+            Ripper::CueRipper => "CUERipper",
+            Ripper::DBPA => "dBpoweramp",
+            Ripper::CyanRip => "cyanrip",
+            Ripper::EZCD => "EZ CD Audio Converter",
+            Ripper::Morituri => "morituri",
+            Ripper::Rip => "Rip (OS X)",
+            Ripper::FreAc => "fre:ac",
+            Ripper::Other => "Other",
+        };
+        write!(f, "{}", name)
+    }
+}
+
 #[derive(Serialize, Deserialize, PartialEq, TS)]
 #[ts(export)]
 pub enum MediaType {
@@ -182,9 +203,26 @@ pub trait Extractor {
         Quartet::Unsupported
     }
 
+    fn extract_max_retry_count(&self) -> Option<u32> {
+        None
+    }
+
+    fn extract_album_gain(&self) -> Option<f64> {
+        None
+    }
+
     fn extract_audio_encoder(&self) -> Vec<String> {
         Vec::new()
     }
+
+    fn extract_ctdb(&self) -> Option<Ctdb> {
+        None
+    }
+
+    // Contradictions that are only visible in the raw log text
+    fn extract_anomalies(&self) -> Vec<Anomaly> {
+        Vec::new()
+    }
 }
 
 pub trait TrackExtractor {
@@ -1,56 +1,98 @@
 use simple_text_decode::DecodedText;
 use xxhash_rust::xxh3::xxh3_64;
 
+use crate::cue::{write::write_cue, CueLayout};
+use crate::detect::{detect_best, split_logs};
 use crate::error::CambiaError;
-use crate::util::{first_line};
-use crate::evaluate::{EvaluationCombined, Evaluator};
+use crate::extract::Ripper;
+use crate::evaluate::{registry, EvaluationCombined};
 use crate::parser::{ParserCombined, ParsedLogCombined};
+use crate::remediation::remediate;
 use crate::response::CambiaResponse;
 
-pub fn detect_ripper(encoded_log: DecodedText) -> Result<Box<dyn ParserCombined>, CambiaError> {
-    match first_line(&encoded_log.text) {
+pub fn detect_ripper(mut encoded_log: DecodedText) -> Result<Box<dyn ParserCombined>, CambiaError> {
+    let detection = detect_best(&encoded_log.text)?;
+
+    tracing::debug!("{} detected with {:.2} confidence", detection.ripper, detection.confidence);
+
+    // Drop whatever was pasted in front of the log
+    if detection.start > 0 {
+        encoded_log.text = encoded_log.text[detection.start..].to_owned();
+    }
+
+    match detection.ripper {
         #[cfg(feature = "eac")]
-        eac if eac.contains("Exact Audio Copy") || eac.contains("EAC") => Ok(Box::new(crate::parser::eac_parser::EacParser::new(encoded_log))),
+        Ripper::EAC => Ok(Box::new(crate::parser::eac_parser::EacParser::new(encoded_log))),
         #[cfg(feature = "xld")]
-        xld if xld.contains("X Lossless Decoder version") => Ok(Box::new(crate::parser::xld_parser::XldParser::new(encoded_log))),
+        Ripper::XLD => Ok(Box::new(crate::parser::xld_parser::XldParser::new(encoded_log))),
         #[cfg(feature = "whipper")]
-        whipper if whipper.contains("Log created by: whipper") => Ok(Box::new(crate::parser::whipper_parser::WhipperParser::new(encoded_log))),
+        Ripper::Whipper => Ok(Box::new(crate::parser::whipper_parser::WhipperParser::new(encoded_log))),
         #[cfg(feature = "cueripper")]
-        cueripper if cueripper.contains("CUERipper") => Ok(Box::new(crate::parser::cueripper_parser::CueRipperParser::new(encoded_log))),
-        cyanrip if cyanrip.contains("cyanrip") => Err(CambiaError::new_anon("cyanrip not supported at the moment.")),
-        dbpa if dbpa.contains("dBpoweramp Release") => Err(CambiaError::new_anon("dBpoweramp not supported at the moment.")),
-        morituri if morituri.contains("Logfile created by: morituri") => Err(CambiaError::new_anon("morituri not supported at the moment.")),
-        ezcd if ezcd.contains("EZ CD Audio Converter") => Err(CambiaError::new_anon("EZ CD Audio Converter not supported at the moment.")),
-        rip if rip.contains("Rip ") && rip.contains(" Audio Extraction Log") => Err(CambiaError::new_anon("Rip (OS X) not supported at the moment.")),
-        freac if freac.contains("Conversion #") => Err(CambiaError::new_anon("fre:ac not supported at the moment.")),
-        _ => Err(CambiaError::new_anon("Unsupported file."))
+        Ripper::CueRipper => Ok(Box::new(crate::parser::cueripper_parser::CueRipperParser::new(encoded_log))),
+        #[cfg(feature = "dbpa")]
+        Ripper::DBPA => Ok(Box::new(crate::parser::dbpa_parser::DbpaParser::new(encoded_log))),
+        #[cfg(feature = "cyanrip")]
+        Ripper::CyanRip => Ok(Box::new(crate::parser::cyanrip_parser::CyanRipParser::new(encoded_log))),
+        #[cfg(feature = "ezcd")]
+        Ripper::EZCD => Ok(Box::new(crate::parser::ezcd_parser::EzcdParser::new(encoded_log))),
+        #[cfg(feature = "morituri")]
+        Ripper::Morituri => Ok(Box::new(crate::parser::morituri_parser::MorituriParser::new(encoded_log))),
+        #[cfg(feature = "rip")]
+        Ripper::Rip => Ok(Box::new(crate::parser::rip_parser::RipParser::new(encoded_log))),
+        #[cfg(feature = "freac")]
+        Ripper::FreAc => Ok(Box::new(crate::parser::freac_parser::FreacParser::new(encoded_log))),
+        Ripper::Other => Err(CambiaError::new_anon("Unsupported file.")),
+        #[allow(unreachable_patterns)]
+        ripper => Err(CambiaError::new_anon(&format!("{} not supported at the moment.", ripper))),
     }
 }
 
-pub fn parse_log_bytes(id: Vec<u8>, log_raw: &Vec<u8>) -> Result<CambiaResponse, CambiaError> {
+// Every log in a concatenated upload gets its own parser
+pub fn detect_rippers(encoded_log: DecodedText) -> Result<Vec<Box<dyn ParserCombined>>, CambiaError> {
+    split_logs(&encoded_log.text)
+        .into_iter()
+        .map(|segment| detect_ripper(DecodedText {
+            text: segment.to_owned(),
+            orig_encoding: encoded_log.orig_encoding.clone(),
+        }))
+        .collect()
+}
+
+// An empty evaluator list runs the default evaluators
+pub fn parse_log_bytes(id: Vec<u8>, log_raw: &Vec<u8>, evaluators: &[String]) -> Result<CambiaResponse, CambiaError> {
     if log_raw.is_empty() {
         return Err(CambiaError::new(id, "Empty request body"));
     }
 
     let res_id = if id.is_empty() { xxh3_64(&log_raw).to_be_bytes().to_vec() } else { id };
+    let mut evaluators = match registry::select(evaluators) {
+        Ok(evaluators) => evaluators,
+        Err(mut e) => {
+            e.id = res_id;
+            return Err(e)
+        },
+    };
     let encoded_log = DecodedText::new(&log_raw).unwrap_or_default();
 
     tracing::debug!("Log {}: {} encoding detected ", hex::encode(&res_id), encoded_log.orig_encoding);
 
-    let parsed_logs: ParsedLogCombined = match detect_ripper(encoded_log) {
-        Ok(parser) => parser.parse_combined(),
+    let encoding = encoded_log.orig_encoding.clone();
+    let parsed_logs: ParsedLogCombined = match detect_rippers(encoded_log) {
+        Ok(parsers) => ParsedLogCombined {
+            parsed_logs: parsers.iter().flat_map(|parser| parser.parse_combined().parsed_logs).collect(),
+            encoding,
+        },
         Err(mut e) => {
             e.id = res_id;
             return Err(e)
         },
     };
 
-    let evaluation_combined: Vec<EvaluationCombined> = vec![
-		#[cfg(feature = "ops_ev")]
-        crate::evaluate::gazelle_evaluate::ops_evaluate::OpsEvaluator::new().evaluate_combined(&parsed_logs),
-		// #[cfg(feature = "cambia_ev")]
-		// crate::evaluate::cambia_evaluate::CambiaEvaluator::new().evaluate_combined(&parsed_logs),
-    ];
+    let mut evaluation_combined: Vec<EvaluationCombined> = evaluators
+        .iter_mut()
+        .map(|evaluator| evaluator.evaluate_combined(&parsed_logs))
+        .collect();
+    remediate(&parsed_logs, &mut evaluation_combined);
     
     Ok(CambiaResponse::new(res_id, parsed_logs, evaluation_combined))
 }
@@ -62,10 +104,24 @@ pub fn translate_log_bytes(log_raw: Vec<u8>) -> Result<String, CambiaError> {
 
     let encoded_log = DecodedText::new(&log_raw).unwrap_or_default();
     
-    match detect_ripper(encoded_log) {
-        Ok(parser) => Ok(parser.translate_combined()),
+    match detect_rippers(encoded_log) {
+        Ok(parsers) => Ok(parsers.iter().map(|parser| parser.translate_combined()).collect::<Vec<String>>().join("\n\n")),
         Err(e) => Err(e),
     }
 }
 
+// One CUE sheet per log, in the same order as the logs
+pub fn cue_from_log_bytes(log_raw: Vec<u8>, layout: Option<CueLayout>) -> Result<String, CambiaError> {
+    if log_raw.is_empty() {
+        return Err(CambiaError::new_anon("Empty request body"));
+    }
+
+    let encoded_log = DecodedText::new(&log_raw).unwrap_or_default();
 
+    detect_rippers(encoded_log)?
+        .iter()
+        .flat_map(|parser| parser.parse_combined().parsed_logs)
+        .map(|parsed_log| write_cue(&parsed_log, layout))
+        .collect::<Result<Vec<String>, CambiaError>>()
+        .map(|cues| cues.join("\n"))
+}
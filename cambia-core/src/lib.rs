@@ -2,11 +2,17 @@
 extern crate lazy_static;
 
 pub mod handler;
+pub mod detect;
 pub mod extract;
 pub mod parser;
 pub mod translate;
 pub mod integrity;
 pub mod toc;
+pub mod cue;
+pub mod ctdb;
+pub mod accurip;
+pub mod anomaly;
+pub mod remediation;
 pub mod track;
 pub mod util;
 pub mod error;
@@ -1,4 +1,6 @@
 pub use crate::extract::{self, Quartet, Ripper, ReadMode, Gap, Extractor, TrackExtractor};
+use crate::anomaly::{check_anomalies, Anomaly};
+use crate::ctdb::Ctdb;
 use crate::toc::Toc;
 use crate::track::TrackEntry;
 pub use crate::translate::Translator;
@@ -13,6 +15,18 @@ pub mod xld_parser;
 pub mod whipper_parser;
 #[cfg(feature = "cueripper")]
 pub mod cueripper_parser;
+#[cfg(feature = "dbpa")]
+pub mod dbpa_parser;
+#[cfg(feature = "cyanrip")]
+pub mod cyanrip_parser;
+#[cfg(feature = "ezcd")]
+pub mod ezcd_parser;
+#[cfg(feature = "morituri")]
+pub mod morituri_parser;
+#[cfg(feature = "rip")]
+pub mod rip_parser;
+#[cfg(feature = "freac")]
+pub mod freac_parser;
 
 use serde::{Serialize, Deserialize};
 use ts_rs::TS;
@@ -48,6 +62,10 @@ pub struct ParsedLog {
     pub tracks: Vec<TrackEntry>,
     pub id3_enabled: Quartet,
     pub audio_encoder: Vec<String>,
+    pub max_retry_count: Option<u32>,
+    pub album_gain: Option<f64>,
+    pub ctdb: Option<Ctdb>,
+    pub anomalies: Vec<Anomaly>,
 }
 
 #[derive(Serialize, Deserialize, TS)]
@@ -59,7 +77,7 @@ pub struct ParsedLogCombined {
 
 pub trait Parser: Extractor + IntegrityChecker {
     fn parse(&mut self) -> ParsedLog {
-        ParsedLog {
+        let mut parsed_log = ParsedLog {
             ripper: self.extract_ripper(),
             ripper_version: self.extract_ripper_version(),
             release_info: self.extract_release_info(),
@@ -84,7 +102,17 @@ pub trait Parser: Extractor + IntegrityChecker {
             tracks: self.extract_tracks(),
             id3_enabled: self.extract_id3_enabled(),
             audio_encoder: self.extract_audio_encoder(),
+            max_retry_count: self.extract_max_retry_count(),
+            album_gain: self.extract_album_gain(),
+            ctdb: self.extract_ctdb(),
+            anomalies: self.extract_anomalies(),
+        };
+        let ripped: Vec<u32> = parsed_log.tracks.iter().filter(|track| !track.is_range).map(|track| u32::from(track.num)).collect();
+        if parsed_log.toc.raw.mark_leading_data(&ripped) {
+            parsed_log.toc = Toc::new(parsed_log.toc.raw.clone());
         }
+        parsed_log.anomalies.append(&mut check_anomalies(&parsed_log));
+        parsed_log
     }
 }
 
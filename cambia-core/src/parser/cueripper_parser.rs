@@ -1,10 +1,10 @@
 use simple_text_decode::DecodedText;
 
-use crate::{translate::TranslatorCombined, extract::{Ripper, Extractor, Quartet, ReadMode, Gap, TrackExtractor}, track::{TrackEntry, TestAndCopy}, toc::{TocEntry, Toc, TocRaw}, util::Time};
+use crate::{ctdb::Ctdb, translate::TranslatorCombined, extract::{Ripper, Extractor, Quartet, ReadMode, Gap, TrackExtractor}, track::{TrackEntry, TestAndCopy, AccurateRipUnit, AccurateRipConfidence, AccurateRipConfidenceTotal, AccurateRipOffset, AccurateRipStatus, TrackError, TrackErrorData, TrackErrorRange}, toc::{TocEntry, Toc, TocRaw}, util::Time};
 
 use super::{eac_parser::EacParserSingle, ParsedLog, ParsedLogCombined, ParserCombined, Parser, IntegrityChecker, ParserTrack};
 
-use regex::{Regex, Captures};
+use regex::{Regex, RegexBuilder, Captures};
 
 lazy_static! {
     static ref RIPPER_VERSION: Regex = Regex::new(r"CUERipper v(.+) Copyright").unwrap();
@@ -20,6 +20,11 @@ lazy_static! {
     static ref FILENAME: Regex = Regex::new(r"    (.+\..\w+)(\r|\n|\r\n|\n\r)").unwrap();
     static ref PREGAP: Regex = Regex::new(r"\s+(?P<track>\d+)\s+\|\s+(?P<pregap>[0-9:]+)\s+\|\s+(?P<indices>\d+)").unwrap();
     static ref PEAK_CRC: Regex = Regex::new(r"\s+(?P<track>\d{2})\s+(?P<peak>[0-9\.]+)\s+\[(?P<crc>[A-F0-9]{8})\]\s+\[(?P<crcnull>[A-F0-9]{8})\]").unwrap();
+
+    static ref AR_NOT_FOUND: Regex = Regex::new(r"\[AccurateRip ID: [0-9a-f\-]+\] disk not present in database").unwrap();
+    static ref AR_OFFSETTED: Regex = Regex::new(r"^Offsetted by (?P<offset>-?\d+):").unwrap();
+    static ref AR_ROW: Regex = Regex::new(r"^\s*(?P<track>\d{2})\s+\[(?P<crc>[0-9a-fA-F]{8})\|(?P<crcv2>[0-9a-fA-F]{8})\]\s+\((?P<v1>\d+)\+(?P<v2>\d+)/(?P<total>\d+)\)\s+(?P<status>.+)$").unwrap();
+    static ref ERRORS: Regex = RegexBuilder::new(r"^\s*(?P<track>\d{2})\s+Suspicious position (?P<start>\d+:\d{2}:\d{2})(\s*-\s*(?P<end>\d+:\d{2}:\d{2}))?").multi_line(true).build().unwrap();
 }
 
 pub struct CueRipperParser {
@@ -35,6 +40,9 @@ pub struct CueRipperParserTrack<'a> {
     filename: String,
     pregap: Captures<'a>,
     peak_crc: Option<Captures<'a>>,
+    ar_not_found: bool,
+    ar_rows: Vec<&'a (Option<i16>, Captures<'a>)>,
+    errors: Vec<&'a Captures<'a>>,
 }
 
 impl CueRipperParser {
@@ -45,9 +53,26 @@ impl CueRipperParser {
     }
 }
 
+impl CueRipperParser {
+    // The EAC-style log of older CUERipper versions goes through the EAC parser
+    fn parse_single(log: String) -> ParsedLog {
+        let mut parser = CueRipperParserSingle::new(log);
+        if !EAC_VARIANT.is_match(&parser.log) {
+            return parser.parse();
+        }
+
+        let mut eac_variant = EacParserSingle::new(parser.log.clone()).parse();
+        eac_variant.ripper = parser.extract_ripper();
+        eac_variant.ripper_version = parser.extract_ripper_version();
+        eac_variant.checksum = parser.get_checksum();
+        eac_variant.id3_enabled = parser.extract_id3_enabled();
+        eac_variant
+    }
+}
+
 impl ParserCombined for CueRipperParser {
     fn parse_combined(&self) -> ParsedLogCombined {        
-        let parsed_logs: Vec<ParsedLog> = vec![CueRipperParserSingle::new(self.encoded_log.text.trim().to_owned()).parse()];
+        let parsed_logs: Vec<ParsedLog> = vec![CueRipperParser::parse_single(self.encoded_log.text.trim().to_owned())];
 
         ParsedLogCombined {
             parsed_logs,
@@ -63,8 +88,8 @@ impl TranslatorCombined for CueRipperParser {
 }
 
 impl<'a> CueRipperParserTrack<'a> {
-    fn new(filename: String, pregap: Captures<'a>, peak_crc: Option<Captures<'a>>) -> Self {
-        Self { filename, pregap, peak_crc }
+    fn new(filename: String, pregap: Captures<'a>, peak_crc: Option<Captures<'a>>, ar_not_found: bool, ar_rows: Vec<&'a (Option<i16>, Captures<'a>)>, errors: Vec<&'a Captures<'a>>) -> Self {
+        Self { filename, pregap, peak_crc, ar_not_found, ar_rows, errors }
     }
 }
 
@@ -75,51 +100,26 @@ impl CueRipperParserSingle {
             language: String::from("English"),
         }
     }
-}
 
-impl Parser for CueRipperParserSingle {
-    fn parse(&mut self) -> ParsedLog {
-        let captures = EAC_VARIANT.captures(&self.log);
-        let parsed_log: ParsedLog = match captures {
-            Some(_) => {
-                let mut eac_variant = EacParserSingle::new(self.log.trim().to_string()).parse();
-                eac_variant.ripper = self.extract_ripper();
-                eac_variant.ripper_version = self.extract_ripper_version();
-                eac_variant.checksum = self.get_checksum();
-                eac_variant.id3_enabled = self.extract_id3_enabled();
-                eac_variant
-            },
-            None => ParsedLog {
-                ripper: self.extract_ripper(),
-                ripper_version: self.extract_ripper_version(),
-                release_info: self.extract_release_info(),
-                language: self.extract_language(),
-                read_offset: self.extract_read_offset(),
-                combined_rw_offset: self.extract_combined_rw_offset(),
-                drive: self.extract_drive(),
-                media_type: self.extract_media_type(),
-                accurate_stream: self.extract_accurate_stream(),
-                defeat_audio_cache: self.extract_defeat_audio_cache(),
-                use_c2: self.extract_use_c2(),
-                overread: self.extract_overread(),
-                fill_silence: self.extract_fill_silence(),
-                delete_silence: self.extract_delete_silence(),
-                use_null_samples: self.extract_use_null_samples(),
-                test_and_copy: self.extract_test_and_copy(),
-                normalize: self.extract_normalize(),
-                read_mode: self.extract_read_mode(),
-                gap_handling: self.extract_gap_handling(),
-                checksum: self.get_checksum(),
-                toc: self.extract_toc(),
-                tracks: self.extract_tracks(),
-                id3_enabled: self.extract_id3_enabled(),
-                audio_encoder: self.extract_audio_encoder(),
-            },
-        };
-        parsed_log
+    // Rows after an "Offsetted by" header only report matches found at that other offset
+    fn extract_ar_rows(&self) -> Vec<(Option<i16>, Captures<'_>)> {
+        let mut rows: Vec<(Option<i16>, Captures<'_>)> = Vec::new();
+        let mut offset: Option<i16> = None;
+
+        for line in self.log.lines() {
+            if let Some(captures) = AR_OFFSETTED.captures(line) {
+                offset = captures.name("offset").unwrap().as_str().parse::<i16>().ok();
+            } else if let Some(captures) = AR_ROW.captures(line) {
+                rows.push((offset, captures));
+            }
+        }
+
+        rows
     }
 }
 
+impl Parser for CueRipperParserSingle {}
+
 impl Extractor for CueRipperParserSingle {
     fn extract_ripper(&self) -> Ripper {
         Ripper::CueRipper
@@ -175,6 +175,10 @@ impl Extractor for CueRipperParserSingle {
         Quartet::False
     }
 
+    fn extract_ctdb(&self) -> Option<Ctdb> {
+        Ctdb::from_log(&self.log)
+    }
+
     fn extract_toc(&self) -> Toc {
         let mut entries: Vec<TocEntry> = Vec::new();
         let captures_all = TOC.captures_iter(&self.log);
@@ -199,14 +203,27 @@ impl Extractor for CueRipperParserSingle {
         let mut peak_crc_all = PEAK_CRC.captures_iter(&self.log);
         let pregap_all = PREGAP.captures_iter(&self.log);
 
+        let ar_not_found = AR_NOT_FOUND.is_match(&self.log);
+        let ar_rows_all = self.extract_ar_rows();
+        let errors_all: Vec<Captures> = ERRORS.captures_iter(&self.log).collect();
+
         for pregap in pregap_all {
+            let num = pregap.name("track").unwrap().as_str().parse::<u8>().unwrap_or_default();
+            let is_track = |c: &Captures| c.name("track").unwrap().as_str().parse::<u8>().unwrap_or_default() == num;
+
+            let ar_rows = ar_rows_all.iter().filter(|(_, c)| is_track(c)).collect();
+            let errors = errors_all.iter().filter(|c| is_track(c)).collect();
+
             let track_parser = CueRipperParserTrack::new(
                 match filename_all.next() {
                     Some(f) => f.get(1).unwrap().as_str().trim_start().to_owned(),
                     None => String::new(),
                 },
                 pregap,
-                peak_crc_all.next()
+                peak_crc_all.next(),
+                ar_not_found,
+                ar_rows,
+                errors,
             );
             tracks.push(track_parser.parse_track());
         }
@@ -266,4 +283,48 @@ impl<'a> TrackExtractor for CueRipperParserTrack<'a> {
 
         TestAndCopy::new(crc.clone(), crc, crc_nonull.clone(), crc_nonull)
     }
+
+    fn extract_errors(&self) -> TrackError {
+        let ranges: Vec<TrackErrorRange> = self.errors.iter()
+            .map(|captures| {
+                let start = Time::from_h_mm_ss(captures.name("start").unwrap().as_str());
+                match captures.name("end") {
+                    Some(end) => TrackErrorRange::new_from_end(start, Time::from_h_mm_ss(end.as_str())),
+                    None => TrackErrorRange::new(start, Time::from_ss("0")),
+                }
+            })
+            .collect();
+
+        TrackError::new_eac(TrackErrorData::new(ranges.len() as u32, ranges), TrackErrorData::default())
+    }
+
+    fn extract_ar_info(&self) -> Vec<AccurateRipUnit> {
+        let mut ars: Vec<AccurateRipUnit> = Vec::new();
+
+        for (offset, captures) in &self.ar_rows {
+            // v1 and v2 matches share the same total, i.e. (05+12/20)
+            let total = captures.name("total").unwrap().as_str().parse::<u32>().unwrap_or_default();
+
+            for (version, sign_key, matching_key) in [(1, "crc", "v1"), (2, "crcv2", "v2")] {
+                let sign = captures.name(sign_key).unwrap().as_str().to_uppercase();
+                let matching = captures.name(matching_key).unwrap().as_str().parse::<u32>().unwrap_or_default();
+                let confidence = |offset| Some(AccurateRipConfidence::new(Some(matching), Some(AccurateRipConfidenceTotal::All(total)), offset));
+
+                let ar = match (offset, matching, total) {
+                    (None, _, 0) => AccurateRipUnit::new(Some(version), sign, String::default(), None, AccurateRipStatus::NotFound),
+                    (None, 0, _) => AccurateRipUnit::new(Some(version), sign, String::default(), confidence(AccurateRipOffset::Same), AccurateRipStatus::Mismatch),
+                    (None, _, _) => AccurateRipUnit::new(Some(version), sign.clone(), sign, confidence(AccurateRipOffset::Same), AccurateRipStatus::Match),
+                    (Some(_), 0, _) => continue,
+                    (Some(offset), _, _) => AccurateRipUnit::new(Some(version), sign.clone(), sign, confidence(AccurateRipOffset::Different(Some(*offset))), AccurateRipStatus::Offsetted),
+                };
+                ars.push(ar);
+            }
+        }
+
+        if ars.is_empty() {
+            ars.push(if self.ar_not_found { AccurateRipUnit::new_eac_notfound() } else { AccurateRipUnit::new_disabled() });
+        }
+
+        ars
+    }
 }
\ No newline at end of file
@@ -0,0 +1,346 @@
+use std::str::FromStr;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{extract::{Extractor, Gap, Quartet, ReadMode, ReleaseInfo, Ripper, TrackExtractor}, integrity::IntegrityChecker, toc::{Toc, TocEntry, TocRaw}, track::{AccurateRipConfidence, AccurateRipConfidenceTotal, AccurateRipOffset, AccurateRipStatus, AccurateRipUnit, TestAndCopy, TrackEntry, TrackError, TrackErrorData}, translate::{Translator, TranslatorCombined}, util::Time};
+use simple_text_decode::DecodedText;
+
+use super::{Parser, ParsedLog, ParserCombined, ParsedLogCombined, ParserTrack};
+
+lazy_static! {
+    static ref RIPPER_VERSION: Regex = Regex::new(r"cyanrip (?P<version>\S+)").unwrap();
+    static ref DEVICE_MODEL: Regex = RegexBuilder::new(r"^Device model:\s+(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref SYSTEM_DEVICE: Regex = RegexBuilder::new(r"^System device:\s+(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref READ_OFFSET_CORRECTION: Regex = RegexBuilder::new(r"^Offset:\s+(?P<value>[+-]?\d+)").multi_line(true).build().unwrap();
+    static ref OVERREAD: Regex = RegexBuilder::new(r"^Overread:\s+(?P<value>\d+)").multi_line(true).build().unwrap();
+    static ref PARANOIA_LEVEL: Regex = RegexBuilder::new(r"^Paranoia level:\s+(?P<value>\w+)").multi_line(true).build().unwrap();
+    static ref PREGAP_ACTION: Regex = RegexBuilder::new(r"^Pregap action:\s+(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref OUTPUTS: Regex = RegexBuilder::new(r"^Outputs:\s+(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref ALBUM: Regex = RegexBuilder::new(r"^\s*album:\s+(?P<value>.+)$").multi_line(true).case_insensitive(true).build().unwrap();
+    static ref ALBUM_ARTIST: Regex = RegexBuilder::new(r"^\s*album_artist:\s+(?P<value>.+)$").multi_line(true).case_insensitive(true).build().unwrap();
+
+    // Track sections open with a line holding nothing but the track number
+    static ref TRACKS: Regex = RegexBuilder::new(r"^Track:?\s+\d+\s*$").multi_line(true).build().unwrap();
+    static ref LOG_EOF: Regex = RegexBuilder::new(r"^Tracks ripped accurately").multi_line(true).build().unwrap();
+
+    static ref TRACK_NUMBER: Regex = Regex::new(r"Track:?\s+(?P<value>\d+)").unwrap();
+    static ref START_LSN: Regex = Regex::new(r"Start LSN:\s+(?P<value>\d+)").unwrap();
+    static ref END_LSN: Regex = Regex::new(r"End LSN:\s+(?P<value>\d+)").unwrap();
+    static ref PREGAP_LSN: Regex = Regex::new(r"Pregap LSN:\s+(?P<value>\d+)").unwrap();
+    static ref PREEMPHASIS: Regex = Regex::new(r"Preemphasis:\s+(?P<value>\w+)").unwrap();
+    static ref FILENAME: Regex = RegexBuilder::new(r"^\s*(File|Output):\s+(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref EAC_CRC: Regex = RegexBuilder::new(r"^\s*EAC CRC32:\s+(?P<value>[A-F0-9]{8})").multi_line(true).build().unwrap();
+    static ref CRC: Regex = RegexBuilder::new(r"^\s*CRC32:\s+(?P<value>[A-F0-9]{8})").multi_line(true).build().unwrap();
+    static ref RIPPING_ERRORS: Regex = Regex::new(r"Ripping errors:\s+(?P<value>\d+)").unwrap();
+    static ref FRAMES_REPAIRED: Regex = Regex::new(r"(?P<value>\d+) frames? repaired").unwrap();
+
+    static ref AR_NOT_FOUND: Regex = Regex::new(r"Accurip:\s+not found").unwrap();
+    static ref AR_RESULT: Regex = Regex::new(r"Accurip v(?P<version>\d):\s+(?P<sign>[A-F0-9]{8}) \((?P<result>accurately ripped|inaccurately ripped|partially accurately ripped|not found|mismatch)(, confidence (?P<cm>\d+)(/(?P<ct>\d+))?)?\)").unwrap();
+}
+
+pub struct CyanRipParser {
+    encoded_log: DecodedText,
+}
+
+struct CyanRipParserSingle {
+    log: String,
+    language: String,
+}
+
+struct CyanRipParserTrack {
+    raw: String,
+}
+
+impl CyanRipParser {
+    pub fn new(encoded_log: DecodedText) -> CyanRipParser {
+        CyanRipParser {
+            encoded_log,
+        }
+    }
+}
+
+impl CyanRipParserSingle {
+    pub fn new(log: String) -> CyanRipParserSingle {
+        let (language, log) = CyanRipParserSingle::translate(log);
+        CyanRipParserSingle {
+            log,
+            language,
+        }
+    }
+
+    fn string_match(&self, regex: &Regex) -> Option<String> {
+        regex.captures(&self.log).map(|c| c.name("value").unwrap().as_str().trim().to_string())
+    }
+
+    fn split_tracks(&self) -> Vec<&str> {
+        let mut raw_tracks: Vec<&str> = Vec::new();
+        let starts: Vec<usize> = TRACKS.find_iter(&self.log).map(|m| m.start()).collect();
+
+        for (idx, start) in starts.iter().enumerate() {
+            let end = match starts.get(idx + 1) {
+                Some(next) => *next,
+                None => LOG_EOF.find_at(&self.log, *start).map_or(self.log.len(), |m| m.start()),
+            };
+            raw_tracks.push(self.log[*start..end].trim());
+        }
+
+        raw_tracks
+    }
+}
+
+impl ParserCombined for CyanRipParser {
+    fn parse_combined(&self) -> ParsedLogCombined {
+        let parsed_logs: Vec<ParsedLog> = vec![CyanRipParserSingle::new(self.encoded_log.text.trim().to_string()).parse()];
+
+        ParsedLogCombined {
+            parsed_logs,
+            encoding: self.encoded_log.orig_encoding.to_string()
+        }
+    }
+}
+
+impl TranslatorCombined for CyanRipParser {
+    fn translate_combined(&self) -> String {
+        self.encoded_log.text.clone()
+    }
+}
+
+impl Parser for CyanRipParserSingle {}
+
+impl Extractor for CyanRipParserSingle {
+    fn extract_ripper(&self) -> Ripper {
+        Ripper::CyanRip
+    }
+
+    fn extract_ripper_version(&self) -> String {
+        let captures = RIPPER_VERSION.captures(&self.log);
+        match captures {
+            Some(captures) => captures.name("version").unwrap().as_str().to_string(),
+            None => String::from("Unknown"),
+        }
+    }
+
+    fn extract_release_info(&self) -> ReleaseInfo {
+        ReleaseInfo::new(
+            self.string_match(&ALBUM_ARTIST).unwrap_or_default(),
+            self.string_match(&ALBUM).unwrap_or_default(),
+        )
+    }
+
+    fn extract_language(&self) -> String {
+        self.language.clone()
+    }
+
+    fn extract_read_offset(&self) -> Option<i16> {
+        self.string_match(&READ_OFFSET_CORRECTION).and_then(|v| v.parse::<i16>().ok())
+    }
+
+    fn extract_drive(&self) -> String {
+        // Model is only logged when the drive reports it, the device path is the fallback
+        self.string_match(&DEVICE_MODEL)
+            .or_else(|| self.string_match(&SYSTEM_DEVICE))
+            .unwrap_or_default()
+    }
+
+    fn extract_overread(&self) -> Quartet {
+        match self.string_match(&OVERREAD).and_then(|v| v.parse::<u32>().ok()) {
+            Some(0) => Quartet::False,
+            Some(_) => Quartet::True,
+            None => Quartet::Unknown,
+        }
+    }
+
+    // Like whipper, cyanrip relies on libcdio-paranoia
+    fn extract_accurate_stream(&self) -> Quartet {
+        Quartet::True
+    }
+
+    fn extract_use_null_samples(&self) -> Quartet {
+        Quartet::True
+    }
+
+    fn extract_read_mode(&self) -> ReadMode {
+        match self.string_match(&PARANOIA_LEVEL) {
+            Some(level) => match level.to_ascii_lowercase().as_str() {
+                "max" => ReadMode::Paranoid,
+                "none" | "0" => ReadMode::Burst,
+                other if other.parse::<u32>().is_ok() => ReadMode::Paranoid,
+                _ => ReadMode::Unknown,
+            },
+            None => ReadMode::Unknown,
+        }
+    }
+
+    fn extract_gap_handling(&self) -> Gap {
+        match self.string_match(&PREGAP_ACTION) {
+            Some(action) => match action.to_ascii_lowercase() {
+                // The default action merges the pregap into the previous track
+                merge if merge.contains("default") || merge.contains("merge") => Gap::Append,
+                drop if drop.contains("drop") => Gap::Discard,
+                track if track.contains("track") => Gap::Prepend,
+                _ => Gap::Unknown,
+            },
+            None => Gap::Unknown,
+        }
+    }
+
+    fn extract_audio_encoder(&self) -> Vec<String> {
+        match self.string_match(&OUTPUTS) {
+            Some(outputs) => outputs
+                .split([',', ' '])
+                .map(|o| o.trim().to_ascii_lowercase())
+                .filter(|o| !o.is_empty())
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    fn extract_toc(&self) -> Toc {
+        let mut entries: Vec<TocEntry> = Vec::new();
+
+        for raw_track in self.split_tracks() {
+            let num = TRACK_NUMBER.captures(raw_track).and_then(|c| c.name("value").unwrap().as_str().parse::<u32>().ok());
+            let start = START_LSN.captures(raw_track).and_then(|c| c.name("value").unwrap().as_str().parse::<u32>().ok());
+            let end = END_LSN.captures(raw_track).and_then(|c| c.name("value").unwrap().as_str().parse::<u32>().ok());
+
+            if let (Some(num), Some(start_sector), Some(end_sector)) = (num, start, end) {
+                entries.push(TocEntry::new(
+                    num,
+                    Time::from_ss(&(start_sector as f64 / 75.0).to_string()),
+                    Time::from_ss(&((end_sector + 1).saturating_sub(start_sector) as f64 / 75.0).to_string()),
+                    start_sector,
+                    end_sector,
+                ));
+            }
+        }
+
+        entries.sort_by_key(|e| e.track);
+
+        Toc::new(TocRaw::new(entries))
+    }
+
+    fn extract_tracks(&self) -> Vec<TrackEntry> {
+        self.split_tracks()
+            .into_iter()
+            .map(|raw_track| CyanRipParserTrack::new(raw_track.to_owned()).parse_track())
+            .collect()
+    }
+}
+
+impl Translator for CyanRipParserSingle {
+    fn translate(log: String) -> (String, String) {
+        (String::from("English"), log)
+    }
+}
+
+// cyanrip logs are not signed
+impl IntegrityChecker for CyanRipParserSingle {}
+
+impl ParserTrack for CyanRipParserTrack {}
+
+impl CyanRipParserTrack {
+    fn new(raw: String) -> Self {
+        CyanRipParserTrack { raw }
+    }
+
+    fn string_match(&self, regex: &Regex) -> String {
+        match regex.captures(&self.raw) {
+            Some(val) => val.name("value").unwrap().as_str().trim().to_string(),
+            None => String::default(),
+        }
+    }
+
+    fn optional_match<T: FromStr>(&self, regex: &Regex) -> Option<T> {
+        regex.captures(&self.raw).and_then(|val| {
+            val.name("value").and_then(|v| v.as_str().trim().parse::<T>().ok())
+        })
+    }
+}
+
+impl TrackExtractor for CyanRipParserTrack {
+    fn extract_num(&self) -> u8 {
+        self.string_match(&TRACK_NUMBER).parse::<u8>().unwrap_or_default()
+    }
+
+    fn extract_is_range(&self) -> bool {
+        false
+    }
+
+    fn extract_filenames(&self) -> Vec<String> {
+        FILENAME.captures_iter(&self.raw)
+            .map(|c| c.name("value").unwrap().as_str().trim().to_owned())
+            .collect()
+    }
+
+    fn extract_pregap_length(&self) -> Option<Time> {
+        let pregap: u32 = self.optional_match(&PREGAP_LSN)?;
+        let start: u32 = self.optional_match(&START_LSN)?;
+        Some(Time::from_ss(&(start.saturating_sub(pregap) as f64 / 75.0).to_string()))
+    }
+
+    fn extract_preemphasis(&self) -> Option<bool> {
+        let value = self.string_match(&PREEMPHASIS).to_ascii_lowercase();
+        match value.as_str() {
+            "" => None,
+            "none" | "no" | "absent" => Some(false),
+            "present" | "yes" => Some(true),
+            _ => None,
+        }
+    }
+
+    fn extract_test_and_copy(&self) -> TestAndCopy {
+        // The EAC-compatible CRC is the one that can be compared with other rippers
+        let mut copy_crc = self.string_match(&EAC_CRC);
+        if copy_crc.is_empty() {
+            copy_crc = self.string_match(&CRC);
+        }
+        TestAndCopy::new_no_skipzero(String::default(), copy_crc)
+    }
+
+    fn extract_errors(&self) -> TrackError {
+        let read = self.optional_match::<u32>(&RIPPING_ERRORS).unwrap_or_default();
+        let repaired = self.optional_match::<u32>(&FRAMES_REPAIRED).unwrap_or_default();
+
+        TrackError::new_eac(TrackErrorData::new_from_count(read), TrackErrorData::new_from_count(repaired))
+    }
+
+    fn extract_ar_info(&self) -> Vec<AccurateRipUnit> {
+        let mut ars: Vec<AccurateRipUnit> = Vec::new();
+
+        for captures in AR_RESULT.captures_iter(&self.raw) {
+            let version = captures.name("version").and_then(|v| v.as_str().parse::<u8>().ok());
+            let sign = captures.name("sign").unwrap().as_str().to_owned();
+            let matching = captures.name("cm").and_then(|m| m.as_str().parse::<u32>().ok());
+            let total = captures.name("ct").and_then(|t| t.as_str().parse::<u32>().ok()).map(AccurateRipConfidenceTotal::Version);
+
+            let ar = match captures.name("result").unwrap().as_str() {
+                "accurately ripped" => AccurateRipUnit::new(
+                    version,
+                    sign.clone(),
+                    sign,
+                    Some(AccurateRipConfidence::new(matching, total, AccurateRipOffset::Same)),
+                    AccurateRipStatus::Match,
+                ),
+                "not found" => AccurateRipUnit::new(version, sign, String::default(), None, AccurateRipStatus::NotFound),
+                _ => AccurateRipUnit::new(
+                    version,
+                    sign,
+                    String::default(),
+                    Some(AccurateRipConfidence::new(matching, total, AccurateRipOffset::Different(None))),
+                    AccurateRipStatus::Mismatch,
+                ),
+            };
+            ars.push(ar);
+        }
+
+        if ars.is_empty() {
+            if AR_NOT_FOUND.is_match(&self.raw) {
+                ars.push(AccurateRipUnit::new_eac_notfound());
+            } else {
+                ars.push(AccurateRipUnit::new_disabled());
+            }
+        }
+
+        ars
+    }
+}
@@ -0,0 +1,339 @@
+use std::str::FromStr;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{extract::{Extractor, Gap, Quartet, ReadMode, Ripper, TrackExtractor}, integrity::IntegrityChecker, toc::{Toc, TocEntry, TocRaw}, track::{AccurateRipConfidence, AccurateRipOffset, AccurateRipStatus, AccurateRipUnit, TestAndCopy, TrackEntry, TrackError, TrackErrorData}, translate::{Translator, TranslatorCombined}, util::Time};
+use simple_text_decode::DecodedText;
+
+use super::{Parser, ParsedLog, ParserCombined, ParsedLogCombined, ParserTrack};
+
+lazy_static! {
+    static ref RIPPER_VERSION: Regex = Regex::new(r"dBpoweramp Release (?P<version>\S+)").unwrap();
+    // Drive string looks like 'D:  [HL-DT-ST - BD-RE  WH16NS40]'
+    static ref USED_DRIVE: Regex = Regex::new(r"Ripping with drive '(\w:)?\s*\[?(?P<drive>[^\]']+)\]?'").unwrap();
+    static ref DRIVE_SEP: Regex = Regex::new(r"\s+-\s+|\s{2,}").unwrap();
+
+    static ref READ_OFFSET_CORRECTION: Regex = Regex::new(r"Drive offset: ([+-]?[0-9]+)").unwrap();
+    static ref OVERREAD: Regex = Regex::new(r"Overread Lead-in/out: (?P<boolean>Yes|No)").unwrap();
+    static ref USE_C2: Regex = Regex::new(r"Using C2: (?P<boolean>Yes|No)").unwrap();
+    static ref CACHE: Regex = Regex::new(r"Cache: (?P<size>\d+) KB").unwrap();
+    static ref FUA_CACHE_INVALIDATE: Regex = Regex::new(r"FUA Cache Invalidate: (?P<boolean>Yes|No)").unwrap();
+    static ref SECURE_SETTINGS: Regex = Regex::new(r"(Bad Sector Re-rip|Ultra)::").unwrap();
+    static ref BURST_SETTINGS: Regex = Regex::new(r"(?i)burst").unwrap();
+    static ref ENCODER: Regex = Regex::new(r"Encoder: (?P<encoder>\S+)").unwrap();
+
+    static ref TRACKS: Regex = RegexBuilder::new(r"^Track \d+:").multi_line(true).build().unwrap();
+    static ref LOG_EOF: Regex = RegexBuilder::new(r"^-{6,}\s*$").multi_line(true).build().unwrap();
+
+    static ref TRACK_NUMBER: Regex = Regex::new(r"Track (?P<value>\d+):").unwrap();
+    static ref TRACK_RANGE: Regex = Regex::new(r"Ripped LBA (?P<start>\d+) to (?P<end>\d+) \((?P<length>[0-9:]+)\) in (?P<time>[0-9:]+)\.").unwrap();
+    static ref FILENAME: Regex = RegexBuilder::new(r"Filename: (?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref COPY_CRC: Regex = Regex::new(r"CRC32: (?P<value>[A-F0-9]{8})").unwrap();
+    static ref INSECURE: Regex = Regex::new(r"Insecure( \((?P<count>\d+) [Ff]rames?\))?").unwrap();
+
+    static ref AR_RESULT: Regex = Regex::new(r"AccurateRip: (?P<result>Accurate|Inaccurate|Not Accurate|Not in Database|Not Present|Track not in Database)( \(confidence (?P<cm>\d+)\))?").unwrap();
+    static ref AR_CRC: Regex = Regex::new(r"AccurateRip CRC: (?P<sign>[A-F0-9]{8})( \(CRCv(?P<version>\d)\))?").unwrap();
+}
+
+pub struct DbpaParser {
+    encoded_log: DecodedText,
+}
+
+struct DbpaParserSingle {
+    log: String,
+    language: String,
+}
+
+struct DbpaParserTrack {
+    raw: String,
+}
+
+impl DbpaParser {
+    pub fn new(encoded_log: DecodedText) -> DbpaParser {
+        DbpaParser {
+            encoded_log,
+        }
+    }
+}
+
+impl DbpaParserSingle {
+    pub fn new(log: String) -> DbpaParserSingle {
+        let (language, log) = DbpaParserSingle::translate(log);
+        DbpaParserSingle {
+            log,
+            language,
+        }
+    }
+
+    fn boolean_matcher(&self, regex: &Regex) -> Quartet {
+        let captures = regex.captures(&self.log);
+        match captures {
+            Some(captures) => {
+                let value = captures.name("boolean").unwrap().as_str();
+                match value {
+                    "Yes" => Quartet::True,
+                    "No" => Quartet::False,
+                    _ => Quartet::Unknown,
+                }
+            },
+            None => Quartet::Unknown,
+        }
+    }
+
+    fn split_tracks(&self) -> Vec<&str> {
+        let mut raw_tracks: Vec<&str> = Vec::new();
+        let starts: Vec<usize> = TRACKS.find_iter(&self.log).map(|m| m.start()).collect();
+
+        for (idx, start) in starts.iter().enumerate() {
+            let end = match starts.get(idx + 1) {
+                Some(next) => *next,
+                // The summary after the last track is fenced off by a dashed line
+                None => LOG_EOF.find_at(&self.log, *start).map_or(self.log.len(), |m| m.start()),
+            };
+            raw_tracks.push(self.log[*start..end].trim());
+        }
+
+        raw_tracks
+    }
+}
+
+impl ParserCombined for DbpaParser {
+    fn parse_combined(&self) -> ParsedLogCombined {
+        let parsed_logs: Vec<ParsedLog> = vec![DbpaParserSingle::new(self.encoded_log.text.trim().to_string()).parse()];
+
+        ParsedLogCombined {
+            parsed_logs,
+            encoding: self.encoded_log.orig_encoding.to_string()
+        }
+    }
+}
+
+impl TranslatorCombined for DbpaParser {
+    fn translate_combined(&self) -> String {
+        self.encoded_log.text.clone()
+    }
+}
+
+impl Parser for DbpaParserSingle {}
+
+impl Extractor for DbpaParserSingle {
+    fn extract_ripper(&self) -> Ripper {
+        Ripper::DBPA
+    }
+
+    fn extract_ripper_version(&self) -> String {
+        let captures = RIPPER_VERSION.captures(&self.log);
+        match captures {
+            Some(captures) => captures.name("version").unwrap().as_str().to_string(),
+            None => String::from("Unknown"),
+        }
+    }
+
+    fn extract_language(&self) -> String {
+        self.language.clone()
+    }
+
+    fn extract_read_offset(&self) -> Option<i16> {
+        let captures = READ_OFFSET_CORRECTION.captures(&self.log);
+        captures.and_then(|captures| captures.get(1).unwrap().as_str().parse::<i16>().ok())
+    }
+
+    fn extract_drive(&self) -> String {
+        let captures = USED_DRIVE.captures(&self.log);
+        match captures {
+            Some(captures) => {
+                let untrimmed = captures.name("drive").unwrap().as_str().trim();
+                DRIVE_SEP.replace_all(untrimmed, " ").to_string()
+            },
+            None => String::default(),
+        }
+    }
+
+    fn extract_defeat_audio_cache(&self) -> Quartet {
+        // dBpoweramp defeats the cache by reading past it, FUA invalidation is the alternative approach
+        let cache_size = CACHE.captures(&self.log).and_then(|c| c.name("size").unwrap().as_str().parse::<u32>().ok());
+        match (cache_size, self.boolean_matcher(&FUA_CACHE_INVALIDATE)) {
+            (_, Quartet::True) => Quartet::True,
+            (Some(size), _) if size > 0 => Quartet::True,
+            (Some(_), _) => Quartet::False,
+            (None, fua) => fua,
+        }
+    }
+
+    fn extract_use_c2(&self) -> Quartet {
+        self.boolean_matcher(&USE_C2)
+    }
+
+    fn extract_overread(&self) -> Quartet {
+        self.boolean_matcher(&OVERREAD)
+    }
+
+    fn extract_use_null_samples(&self) -> Quartet {
+        Quartet::True
+    }
+
+    fn extract_read_mode(&self) -> ReadMode {
+        if SECURE_SETTINGS.is_match(&self.log) {
+            ReadMode::Secure
+        } else if BURST_SETTINGS.is_match(&self.log) {
+            ReadMode::Burst
+        } else {
+            ReadMode::Unknown
+        }
+    }
+
+    // dBpoweramp does not log how gaps were handled
+    fn extract_gap_handling(&self) -> Gap {
+        Gap::Unknown
+    }
+
+    fn extract_audio_encoder(&self) -> Vec<String> {
+        let captures = ENCODER.captures(&self.log);
+        match captures {
+            Some(captures) => vec![captures.name("encoder").unwrap().as_str().trim().to_ascii_lowercase()],
+            None => Vec::new(),
+        }
+    }
+
+    fn extract_toc(&self) -> Toc {
+        let mut entries: Vec<TocEntry> = Vec::new();
+
+        for raw_track in self.split_tracks() {
+            let num = match TRACK_NUMBER.captures(raw_track) {
+                Some(c) => c.name("value").unwrap().as_str().parse::<u32>().unwrap_or_default(),
+                None => continue,
+            };
+
+            if let Some(c) = TRACK_RANGE.captures(raw_track) {
+                let start_sector: u32 = c.name("start").unwrap().as_str().parse().unwrap_or_default();
+                // The end LBA is exclusive, it is the start of the next track
+                let end_sector: u32 = c.name("end").unwrap().as_str().parse::<u32>().unwrap_or_default().saturating_sub(1);
+                entries.push(TocEntry::new(
+                    num,
+                    Time::from_ss(&(start_sector as f64 / 75.0).to_string()),
+                    Time::from_ss(&((end_sector + 1).saturating_sub(start_sector) as f64 / 75.0).to_string()),
+                    start_sector,
+                    end_sector,
+                ));
+            }
+        }
+
+        Toc::new(TocRaw::new(entries))
+    }
+
+    fn extract_tracks(&self) -> Vec<TrackEntry> {
+        self.split_tracks()
+            .into_iter()
+            .map(|raw_track| DbpaParserTrack::new(raw_track.to_owned()).parse_track())
+            .collect()
+    }
+}
+
+impl Translator for DbpaParserSingle {
+    fn translate(log: String) -> (String, String) {
+        (String::from("English"), log)
+    }
+}
+
+// dBpoweramp logs are not signed
+impl IntegrityChecker for DbpaParserSingle {}
+
+impl ParserTrack for DbpaParserTrack {}
+
+impl DbpaParserTrack {
+    fn new(raw: String) -> Self {
+        DbpaParserTrack { raw }
+    }
+
+    fn string_match(&self, regex: &Regex) -> String {
+        match regex.captures(&self.raw) {
+            Some(val) => val.name("value").unwrap().as_str().trim().to_string(),
+            None => String::default(),
+        }
+    }
+
+    fn optional_match<T: FromStr>(&self, regex: &Regex, name: &str) -> Option<T> {
+        regex.captures(&self.raw).and_then(|val| {
+            val.name(name).and_then(|v| v.as_str().trim().parse::<T>().ok())
+        })
+    }
+
+    fn mm_ss_to_secs(mm_ss: &str) -> Option<f64> {
+        let (m, s) = mm_ss.split_once(':')?;
+        Some(m.parse::<f64>().ok()? * 60.0 + s.parse::<f64>().ok()?)
+    }
+}
+
+impl TrackExtractor for DbpaParserTrack {
+    fn extract_num(&self) -> u8 {
+        self.string_match(&TRACK_NUMBER).parse::<u8>().unwrap_or_default()
+    }
+
+    fn extract_is_range(&self) -> bool {
+        false
+    }
+
+    fn extract_filenames(&self) -> Vec<String> {
+        let filename = self.string_match(&FILENAME);
+        if filename.is_empty() { Vec::new() } else { vec![filename] }
+    }
+
+    fn extract_extraction_speed(&self) -> Option<f64> {
+        let captures = TRACK_RANGE.captures(&self.raw)?;
+        let length = Self::mm_ss_to_secs(captures.name("length").unwrap().as_str())?;
+        let time = Self::mm_ss_to_secs(captures.name("time").unwrap().as_str())?;
+
+        if time > 0.0 { Some(length / time) } else { None }
+    }
+
+    fn extract_test_and_copy(&self) -> TestAndCopy {
+        // No test pass, dBpoweramp verifies through AccurateRip and re-reads instead
+        TestAndCopy::new_no_skipzero(String::default(), self.string_match(&COPY_CRC))
+    }
+
+    fn extract_errors(&self) -> TrackError {
+        let insecure = match INSECURE.captures(&self.raw) {
+            // Older releases only flag the track without the frame count
+            Some(c) => c.name("count").map_or(1, |v| v.as_str().parse::<u32>().unwrap_or(1)),
+            None => 0,
+        };
+
+        TrackError::new_eac(TrackErrorData::new_from_count(insecure), TrackErrorData::default())
+    }
+
+    fn extract_ar_info(&self) -> Vec<AccurateRipUnit> {
+        let mut ars: Vec<AccurateRipUnit> = Vec::new();
+
+        let captures = match AR_RESULT.captures(&self.raw) {
+            Some(c) => c,
+            None => {
+                ars.push(AccurateRipUnit::new_disabled());
+                return ars;
+            }
+        };
+
+        let sign = self.optional_match::<String>(&AR_CRC, "sign").unwrap_or_default();
+        let version = self.optional_match::<u8>(&AR_CRC, "version");
+        let matching = captures.name("cm").and_then(|m| m.as_str().parse::<u32>().ok());
+
+        let (status, offset) = match captures.name("result").unwrap().as_str() {
+            "Accurate" => (AccurateRipStatus::Match, AccurateRipOffset::Same),
+            "Inaccurate" | "Not Accurate" => (AccurateRipStatus::Mismatch, AccurateRipOffset::Different(None)),
+            _ => {
+                ars.push(AccurateRipUnit::new(version, sign, String::default(), None, AccurateRipStatus::NotFound));
+                return ars;
+            },
+        };
+
+        let offset_sign = if status == AccurateRipStatus::Match { sign.clone() } else { String::default() };
+        ars.push(AccurateRipUnit::new(
+            version,
+            sign,
+            offset_sign,
+            Some(AccurateRipConfidence::new(matching, None, offset)),
+            status,
+        ));
+
+        ars
+    }
+}
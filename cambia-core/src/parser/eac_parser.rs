@@ -8,7 +8,7 @@ use phf::OrderedMap;
 use regex::{Regex, RegexBuilder};
 use rayon::prelude::*;
 
-use crate::{extract::{Extractor, Gap, Quartet, ReadMode, ReleaseInfo, Ripper, TrackExtractor}, integrity::IntegrityChecker, toc::{Toc, TocEntry, TocRaw}, track::{TestAndCopy, TrackEntry, TrackError, TrackErrorData, TrackErrorRange, AccurateRipUnit}, translate::{Translator, TranslatorCombined}, util::Time};
+use crate::{anomaly::{Anomaly, AnomalyKind}, ctdb::Ctdb, extract::{Extractor, Gap, Quartet, ReadMode, ReleaseInfo, Ripper, TrackExtractor}, integrity::IntegrityChecker, toc::{Toc, TocEntry, TocRaw}, track::{TestAndCopy, TrackEntry, TrackError, TrackErrorData, TrackErrorRange, AccurateRipUnit}, translate::{Translator, TranslatorCombined}, util::Time};
 use simple_text_decode::DecodedText;
 
 use self::{translation_table::{LANGS, L_DUMMY_MAP, L_47AB3DF2_MAP}, rijndael::Rijndael};
@@ -18,6 +18,7 @@ use super::{Parser, ParsedLog, ParserCombined, ParsedLogCombined, ParserTrack};
 static SPLIT_SEP: &str = "\r\n------------------------------------------------------------\r\n";
 
 lazy_static! {
+    static ref SPLIT_SEP_ANY: Regex = Regex::new(r"\r?\n-{60}\r?\n").unwrap();
     static ref RIPPER_VERSION: Regex = Regex::new(r"Exact Audio Copy (.+) from").unwrap();
     // Drive regex is a bit looser due to certain translations replacing the colon
     // Since replacement is based on longest match, it cannot be overridden with custom translation rules
@@ -87,10 +88,8 @@ impl EacParser {
     }
 
     pub fn split_combined(&self) -> Vec<&str> {
-        /* TODO: When the log anomaly pipeline is implemented
-           Make sure that combined logs that don't use this sep are detected and handled */
-        // TODO: Might need to use str::split_inclusive in the future
-        self.encoded_log.text.split(SPLIT_SEP).collect::<Vec<_>>()
+        // Logs are usually split by detect::split_logs already, this catches headerless ones
+        SPLIT_SEP_ANY.split(&self.encoded_log.text).collect::<Vec<_>>()
     }
 }
 
@@ -125,6 +124,17 @@ impl EacParserSingle {
         }
     }
 
+    // Newer logs carry both the settings lines and the legacy read mode line
+    fn legacy_conflict(&self, regex: &Regex, regex_legacy: &Regex, setting: &str) -> Option<Anomaly> {
+        match (self.boolean_matcher(regex), self.boolean_matcher_legacy(regex_legacy)) {
+            (Quartet::True, Quartet::False) | (Quartet::False, Quartet::True) => Some(Anomaly::new_release(
+                AnomalyKind::ConflictingSettings,
+                format!("\"{}\" contradicts the read mode line", setting),
+            )),
+            _ => None,
+        }
+    }
+
     fn boolean_matcher_legacy(&self, regex: &Regex) -> Quartet {
         let captures = regex.captures(&self.translated_log);
         match captures {
@@ -258,6 +268,14 @@ impl Extractor for EacParserSingle {
         self.boolean_matcher(&USE_NULL_SAMPLES)
     }
 
+    fn extract_anomalies(&self) -> Vec<Anomaly> {
+        [
+            self.legacy_conflict(&ACCURATE_STREAM, &ACCURATE_STREAM_LEGACY, "Utilize accurate stream"),
+            self.legacy_conflict(&DEFEAT_AUDIO_CACHE, &DEFEAT_AUDIO_CACHE_LEGACY, "Defeat audio cache"),
+            self.legacy_conflict(&USE_C2, &USE_C2_LEGACY, "Make use of C2 pointers"),
+        ].into_iter().flatten().collect()
+    }
+
     fn extract_id3_enabled(&self) -> Quartet {
         self.boolean_matcher(&ID3_ENABLED)
     }
@@ -337,6 +355,10 @@ impl Extractor for EacParserSingle {
         }
     }
 
+    fn extract_ctdb(&self) -> Option<Ctdb> {
+        Ctdb::from_log(&self.log)
+    }
+
     fn extract_toc(&self) -> Toc {
         let mut entries: Vec<TocEntry> = Vec::new();
         let captures_all = TOC.captures_iter(&self.translated_log);
@@ -0,0 +1,446 @@
+use std::str::FromStr;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{ctdb::{Ctdb, CtdbConfidence, CtdbStatus, CtdbTrack}, extract::{Extractor, Gap, MediaType, Quartet, ReadMode, ReleaseInfo, Ripper, TrackExtractor}, integrity::IntegrityChecker, toc::{Toc, TocEntry, TocRaw}, track::{AccurateRipUnit, TestAndCopy, TrackEntry, TrackError, TrackErrorData, TrackErrorRange}, translate::{Translator, TranslatorCombined}, util::Time};
+use simple_text_decode::DecodedText;
+
+use super::{Parser, ParsedLog, ParserCombined, ParsedLogCombined, ParserTrack};
+
+lazy_static! {
+    static ref RIPPER_VERSION: Regex = Regex::new(r"EZ CD Audio Converter (?P<version>[0-9.]+)").unwrap();
+    static ref RELEASE_INFO: Regex = Regex::new(r"extraction logfile from .+[\r\n]+(?P<relinfo>.+)").unwrap();
+    static ref USED_DRIVE: Regex = Regex::new(r"Used drive( *): (?P<drive>.+)").unwrap();
+    static ref DRIVE_TRIM: Regex = Regex::new(r"\s*\((SCSI|Adapter)[^)]*\)").unwrap();
+    static ref DRIVE_SEP: Regex = Regex::new(r"\s{2,}").unwrap();
+    static ref MEDIA_TYPE: Regex = Regex::new(r"Media type( *): (?P<value>.+)").unwrap();
+
+    static ref READ_MODE: Regex = RegexBuilder::new(r"Read mode( *): (?P<value>\w+)").case_insensitive(true).build().unwrap();
+    static ref ACCURATE_STREAM: Regex = RegexBuilder::new(r"(Utilize )?accurate stream( *): (?P<boolean>Yes|No)").case_insensitive(true).build().unwrap();
+    static ref DEFEAT_AUDIO_CACHE: Regex = RegexBuilder::new(r"Defeat audio cache( *): (?P<boolean>Yes|No)").case_insensitive(true).build().unwrap();
+    static ref USE_C2: Regex = RegexBuilder::new(r"(Use|Make use of) C2 (error )?pointers( *): (?P<boolean>Yes|No)").case_insensitive(true).build().unwrap();
+    static ref READ_OFFSET_CORRECTION: Regex = RegexBuilder::new(r"Read offset correction( *): (?P<value>[+-]?[0-9]+)").case_insensitive(true).build().unwrap();
+    static ref OVERREAD: Regex = RegexBuilder::new(r"Overread into lead-in and lead-out( *): (?P<boolean>Yes|No)").case_insensitive(true).build().unwrap();
+    static ref FILL_SILENCE: Regex = RegexBuilder::new(r"Fill up missing offset samples with silence( *): (?P<boolean>Yes|No)").case_insensitive(true).build().unwrap();
+    static ref DELETE_SILENCE: Regex = RegexBuilder::new(r"Delete leading and trailing silent blocks( *): (?P<boolean>Yes|No)").case_insensitive(true).build().unwrap();
+    static ref USE_NULL_SAMPLES: Regex = RegexBuilder::new(r"Null samples used in CRC calculations( *): (?P<boolean>Yes|No)").case_insensitive(true).build().unwrap();
+    static ref NORMALIZE: Regex = RegexBuilder::new(r"Normalize( to)?( *): (?P<boolean>Yes|No|[0-9.]+ ?%)").case_insensitive(true).build().unwrap();
+    static ref GAP_HANDLING: Regex = RegexBuilder::new(r"Gap handling( *): (?P<value>.+)").case_insensitive(true).build().unwrap();
+    static ref ID3_ENABLED: Regex = RegexBuilder::new(r"Add ID3 tag( *): (?P<boolean>Yes|No)").case_insensitive(true).build().unwrap();
+    static ref ENCODER: Regex = RegexBuilder::new(r"(Used output format|Encoder)( *): (.*)(?P<fmt>flac|wav|mp3|m4a|ape|tta|ogg|opus|wv|alac|aiff)").case_insensitive(true).build().unwrap();
+
+    static ref TEST_AND_COPY: Regex = Regex::new(r"Test CRC(32)?\s+([0-9A-F]{8})").unwrap();
+    static ref TOC: Regex = Regex::new(r"\s+(?P<track>\d+)\s+\|\s+(?P<start>[0-9:\.]+)\s+\|\s+(?P<length>[0-9:\.]+)\s+\|\s+(?P<start_sector>\d+)\s+\|\s+(?P<end_sector>\d+)").unwrap();
+
+    static ref SPLIT_TRACKS: Regex = RegexBuilder::new(r"Track\s*\d+\s*[\r\n].+?Copy (OK|finished|aborted|failed)").dot_matches_new_line(true).build().unwrap();
+
+    static ref TRACK_NUMBER: Regex = Regex::new(r"Track\s*(?P<value>\d+)").unwrap();
+    static ref COPY_ABORTED: Regex = Regex::new(r"Copy (aborted|failed)").unwrap();
+    static ref FILENAME: Regex = RegexBuilder::new(r"Filename( *:)? (?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref PREGAP: Regex = Regex::new(r"Pre-gap length( *:)?\s*(?P<time>\d+:\d{2}:\d{2}\.\d{2})").unwrap();
+    static ref PEAK_LEVEL: Regex = Regex::new(r"Peak level( *:)? (?P<value>[0-9.]+) ?%").unwrap();
+    static ref EXTRACTION_SPEED: Regex = Regex::new(r"Extraction speed( *:)? (?P<value>[0-9.]+) ?X").unwrap();
+    static ref TEST_CRC: Regex = Regex::new(r"Test CRC(32)?( *:)? (?P<value>[A-F0-9]{8})").unwrap();
+    static ref COPY_CRC: Regex = Regex::new(r"Copy CRC(32)?( *:)? (?P<value>[A-F0-9]{8})").unwrap();
+    static ref ERROR: Regex = Regex::new(r"(?P<type>Suspicious position|Timing problem|Read error)(\s*)(?P<start>\d+:\d{2}:\d{2})( - (?P<end>\d+:\d{2}:\d{2}))?").unwrap();
+
+    static ref AR_FOUND: Regex = Regex::new(r"Accurately ripped \(confidence (?P<cm>\d+)\)\s+\[(?P<sign>[A-F0-9]{8})\]\s+\(AR v(?P<version>\d+)\)").unwrap();
+    static ref AR_MISMATCH: Regex = Regex::new(r"Cannot be verified as accurate \(confidence (?P<cm>\d+)\)\s+\[(?P<sign>[A-F0-9]{8})\], AccurateRip returned \[(?P<off_sign>[A-F0-9]{8})\]\s+\(AR v(?P<version>\d+)\)").unwrap();
+    static ref AR_NO_DB: Regex = Regex::new(r"Track not present in AccurateRip database").unwrap();
+
+    static ref CTDB_RESULT: Regex = Regex::new(r"CTDB( *:)?\s*(?P<status>[^(\r\n]+?)\s*\(confidence (?P<cm>\d+)(/(?P<ct>\d+))?\)").unwrap();
+    static ref CTDB_NO_DB: Regex = Regex::new(r"Track not present in CTDB").unwrap();
+}
+
+pub struct EzcdParser {
+    encoded_log: DecodedText,
+}
+
+struct EzcdParserSingle {
+    log: String,
+    language: String,
+}
+
+struct EzcdParserTrack {
+    use_null_samples: Quartet,
+    raw: String,
+}
+
+impl EzcdParser {
+    pub fn new(encoded_log: DecodedText) -> EzcdParser {
+        EzcdParser {
+            encoded_log,
+        }
+    }
+}
+
+impl EzcdParserSingle {
+    pub fn new(log: String) -> EzcdParserSingle {
+        let (language, log) = EzcdParserSingle::translate(log);
+        EzcdParserSingle {
+            log,
+            language,
+        }
+    }
+
+    fn boolean_matcher(&self, regex: &Regex) -> Quartet {
+        let captures = regex.captures(&self.log);
+        match captures {
+            Some(captures) => {
+                let value = captures.name("boolean").unwrap().as_str();
+                match value.to_ascii_lowercase().as_str() {
+                    "yes" => Quartet::True,
+                    "no" => Quartet::False,
+                    _ => Quartet::Unknown,
+                }
+            },
+            None => Quartet::Unknown,
+        }
+    }
+
+    fn string_match(&self, regex: &Regex) -> Option<String> {
+        regex.captures(&self.log).map(|c| c.name("value").unwrap().as_str().trim().to_string())
+    }
+}
+
+impl ParserCombined for EzcdParser {
+    fn parse_combined(&self) -> ParsedLogCombined {
+        let parsed_logs: Vec<ParsedLog> = vec![EzcdParserSingle::new(self.encoded_log.text.trim().to_string()).parse()];
+
+        ParsedLogCombined {
+            parsed_logs,
+            encoding: self.encoded_log.orig_encoding.to_string()
+        }
+    }
+}
+
+impl TranslatorCombined for EzcdParser {
+    fn translate_combined(&self) -> String {
+        self.encoded_log.text.clone()
+    }
+}
+
+impl Parser for EzcdParserSingle {}
+
+impl Extractor for EzcdParserSingle {
+    fn extract_ripper(&self) -> Ripper {
+        Ripper::EZCD
+    }
+
+    fn extract_ripper_version(&self) -> String {
+        let captures = RIPPER_VERSION.captures(&self.log);
+        match captures {
+            Some(captures) => captures.name("version").unwrap().as_str().to_string(),
+            None => String::from("Unknown"),
+        }
+    }
+
+    fn extract_release_info(&self) -> ReleaseInfo {
+        let captures = RELEASE_INFO.captures(&self.log);
+        match captures {
+            Some(captures) => {
+                let split = captures.name("relinfo").unwrap().as_str().split_once(" / ");
+                match split {
+                    Some(s) => ReleaseInfo::new(s.0.trim().to_owned(), s.1.trim().to_owned()),
+                    None => ReleaseInfo::default(),
+                }
+            },
+            None => ReleaseInfo::default(),
+        }
+    }
+
+    fn extract_language(&self) -> String {
+        self.language.clone()
+    }
+
+    fn extract_read_offset(&self) -> Option<i16> {
+        self.string_match(&READ_OFFSET_CORRECTION).and_then(|v| v.parse::<i16>().ok())
+    }
+
+    fn extract_drive(&self) -> String {
+        let captures = USED_DRIVE.captures(&self.log);
+        match captures {
+            Some(captures) => {
+                let untrimmed = captures.name("drive").unwrap().as_str().trim();
+                let drive = DRIVE_TRIM.replace_all(untrimmed, "");
+                DRIVE_SEP.replace_all(drive.trim(), " ").to_string()
+            },
+            None => String::default(),
+        }
+    }
+
+    fn extract_media_type(&self) -> MediaType {
+        match self.string_match(&MEDIA_TYPE) {
+            Some(value) => match value.as_str() {
+                pressed if pressed.contains("Pressed") || pressed.contains("CD-ROM") => MediaType::Pressed,
+                cdr if cdr.contains("CD-R") => MediaType::CDR,
+                _ => MediaType::Other,
+            },
+            None => MediaType::Unknown,
+        }
+    }
+
+    fn extract_accurate_stream(&self) -> Quartet {
+        self.boolean_matcher(&ACCURATE_STREAM)
+    }
+
+    fn extract_defeat_audio_cache(&self) -> Quartet {
+        self.boolean_matcher(&DEFEAT_AUDIO_CACHE)
+    }
+
+    fn extract_use_c2(&self) -> Quartet {
+        self.boolean_matcher(&USE_C2)
+    }
+
+    fn extract_overread(&self) -> Quartet {
+        self.boolean_matcher(&OVERREAD)
+    }
+
+    fn extract_fill_silence(&self) -> Quartet {
+        self.boolean_matcher(&FILL_SILENCE)
+    }
+
+    fn extract_delete_silence(&self) -> Quartet {
+        self.boolean_matcher(&DELETE_SILENCE)
+    }
+
+    fn extract_use_null_samples(&self) -> Quartet {
+        self.boolean_matcher(&USE_NULL_SAMPLES)
+    }
+
+    fn extract_test_and_copy(&self) -> Quartet {
+        match TEST_AND_COPY.is_match(&self.log) {
+            true => Quartet::True,
+            false => Quartet::False,
+        }
+    }
+
+    fn extract_normalize(&self) -> Quartet {
+        match NORMALIZE.captures(&self.log) {
+            Some(captures) => match captures.name("boolean").unwrap().as_str().to_ascii_lowercase().as_str() {
+                "no" => Quartet::False,
+                _ => Quartet::True,
+            },
+            None => Quartet::False,
+        }
+    }
+
+    fn extract_read_mode(&self) -> ReadMode {
+        match self.string_match(&READ_MODE) {
+            Some(value) => match value.to_ascii_lowercase().as_str() {
+                "secure" => ReadMode::Secure,
+                "paranoid" => ReadMode::Paranoid,
+                "fast" => ReadMode::Fast,
+                "burst" => ReadMode::Burst,
+                _ => ReadMode::Unknown,
+            },
+            None => ReadMode::Unknown,
+        }
+    }
+
+    fn extract_gap_handling(&self) -> Gap {
+        match self.string_match(&GAP_HANDLING) {
+            Some(value) => match value.to_ascii_lowercase() {
+                unknown if unknown.contains("not detected") => Gap::AppendUndetected,
+                prepend if prepend.contains("next track") => Gap::Prepend,
+                discard if discard.contains("left out") || discard.contains("discard") => Gap::Discard,
+                append if append.contains("previous track") || append.contains("append") => Gap::Append,
+                _ => Gap::Unknown,
+            },
+            None => Gap::Unknown,
+        }
+    }
+
+    fn extract_id3_enabled(&self) -> Quartet {
+        self.boolean_matcher(&ID3_ENABLED)
+    }
+
+    fn extract_audio_encoder(&self) -> Vec<String> {
+        match ENCODER.captures(&self.log) {
+            Some(captures) => vec![captures.name("fmt").unwrap().as_str().to_ascii_lowercase()],
+            None => Vec::new(),
+        }
+    }
+
+    fn extract_toc(&self) -> Toc {
+        let mut entries: Vec<TocEntry> = Vec::new();
+        let captures_all = TOC.captures_iter(&self.log);
+
+        // Lines with numbers out of range are skipped, TocRaw::new rejects the gap they leave
+        for captures in captures_all {
+            let (Ok(track), Ok(start_sector), Ok(end_sector)) = (captures["track"].parse(), captures["start_sector"].parse(), captures["end_sector"].parse()) else {
+                continue;
+            };
+            entries.push(TocEntry::new(
+                track,
+                Time::from_mm_ss(&captures["start"]),
+                Time::from_mm_ss(&captures["length"]),
+                start_sector,
+                end_sector,
+            ))
+        }
+
+        Toc::new(TocRaw::new(entries))
+    }
+
+    // EZCD only reports CTDB per track, the TOC ID is derived from the TOC instead
+    fn extract_ctdb(&self) -> Option<Ctdb> {
+        let mut tracks: Vec<CtdbTrack> = Vec::new();
+
+        for raw_track in SPLIT_TRACKS.find_iter(&self.log).map(|m| m.as_str()) {
+            let num = TRACK_NUMBER.captures(raw_track).and_then(|c| c.name("value").unwrap().as_str().parse::<u8>().ok()).unwrap_or_default();
+
+            if CTDB_NO_DB.is_match(raw_track) {
+                tracks.push(CtdbTrack::new(num, CtdbConfidence::new(0, 0), None, CtdbStatus::NotFound));
+            } else if let Some(captures) = CTDB_RESULT.captures(raw_track) {
+                let (status, differing_samples) = CtdbStatus::from_text(captures.name("status").unwrap().as_str());
+                let matching = captures.name("cm").unwrap().as_str().parse::<u32>().unwrap_or_default();
+                let total = captures.name("ct").map_or(matching, |t| t.as_str().parse::<u32>().unwrap_or_default());
+                tracks.push(CtdbTrack::new(num, CtdbConfidence::new(matching, total), differing_samples, status));
+            }
+        }
+
+        if tracks.is_empty() {
+            return None;
+        }
+
+        Some(Ctdb::new_from_tracks(self.extract_toc().ctdb_tocid.hash, tracks))
+    }
+
+    fn extract_tracks(&self) -> Vec<TrackEntry> {
+        let null_flag = self.extract_use_null_samples();
+
+        SPLIT_TRACKS.captures_iter(&self.log)
+            .map(|captures| EzcdParserTrack::new(null_flag, captures.get(0).unwrap().as_str().to_string()).parse_track())
+            .collect()
+    }
+}
+
+impl Translator for EzcdParserSingle {
+    fn translate(log: String) -> (String, String) {
+        (String::from("English"), log)
+    }
+}
+
+// EZ CD Audio Converter does not sign its logs
+impl IntegrityChecker for EzcdParserSingle {}
+
+impl ParserTrack for EzcdParserTrack {}
+
+impl EzcdParserTrack {
+    fn new(use_null_samples: Quartet, raw: String) -> Self {
+        EzcdParserTrack { use_null_samples, raw }
+    }
+
+    fn string_match(&self, regex: &Regex) -> String {
+        match regex.captures(&self.raw) {
+            Some(val) => val.name("value").unwrap().as_str().trim().to_string(),
+            None => String::default(),
+        }
+    }
+
+    fn optional_match<T: FromStr>(&self, regex: &Regex) -> Option<T> {
+        regex.captures(&self.raw).and_then(|val| {
+            val.name("value").and_then(|v| v.as_str().trim().parse::<T>().ok())
+        })
+    }
+}
+
+impl TrackExtractor for EzcdParserTrack {
+    fn extract_num(&self) -> u8 {
+        self.string_match(&TRACK_NUMBER).parse::<u8>().unwrap_or_default()
+    }
+
+    fn extract_is_range(&self) -> bool {
+        false
+    }
+
+    fn extract_is_aborted(&self) -> bool {
+        COPY_ABORTED.is_match(&self.raw)
+    }
+
+    fn extract_filenames(&self) -> Vec<String> {
+        let filename = self.string_match(&FILENAME);
+        if filename.is_empty() { Vec::new() } else { vec![filename] }
+    }
+
+    fn extract_peak_level(&self) -> Option<f64> {
+        self.optional_match::<f64>(&PEAK_LEVEL).map(|val| val / 100.0)
+    }
+
+    fn extract_pregap_length(&self) -> Option<Time> {
+        let captures = PREGAP.captures(&self.raw);
+        captures.map(|captures| Time::from_h_mm_ss(captures.name("time").unwrap().as_str()))
+    }
+
+    fn extract_extraction_speed(&self) -> Option<f64> {
+        self.optional_match(&EXTRACTION_SPEED)
+    }
+
+    fn extract_test_and_copy(&self) -> TestAndCopy {
+        let test_crc = self.string_match(&TEST_CRC);
+        let copy_crc = self.string_match(&COPY_CRC);
+
+        match self.use_null_samples {
+            Quartet::False => TestAndCopy::new_skipzero(test_crc, copy_crc),
+            _ => TestAndCopy::new_no_skipzero(test_crc, copy_crc),
+        }
+    }
+
+    fn extract_errors(&self) -> TrackError {
+        let mut read_errors: Vec<TrackErrorRange> = Vec::new();
+        let mut jitter_errors: Vec<TrackErrorRange> = Vec::new();
+
+        for captures in ERROR.captures_iter(&self.raw) {
+            let start = Time::from_h_mm_ss(captures.name("start").unwrap().as_str());
+            let error_range = match captures.name("end") {
+                Some(end) => TrackErrorRange::new_from_end(start, Time::from_h_mm_ss(end.as_str())),
+                None => TrackErrorRange::new(start, Time::from_ss("0")),
+            };
+
+            match captures.name("type").unwrap().as_str() {
+                "Timing problem" => jitter_errors.push(error_range),
+                _ => read_errors.push(error_range),
+            }
+        }
+
+        TrackError::new_eac(
+            TrackErrorData::new(read_errors.len() as u32, read_errors),
+            TrackErrorData::new(jitter_errors.len() as u32, jitter_errors)
+        )
+    }
+
+    fn extract_ar_info(&self) -> Vec<AccurateRipUnit> {
+        let mut ars: Vec<AccurateRipUnit> = Vec::new();
+
+        if AR_NO_DB.is_match(&self.raw) {
+            ars.push(AccurateRipUnit::new_eac_notfound());
+            return ars;
+        }
+
+        // Unlike EAC, both AR versions can be listed for the same track
+        // Lines with a version or confidence out of range are skipped
+        for ar_f_raw in AR_FOUND.captures_iter(&self.raw) {
+            let (Ok(version), Ok(matching)) = (ar_f_raw["version"].parse::<u8>(), ar_f_raw["cm"].parse::<u32>()) else {
+                continue;
+            };
+            let sign = ar_f_raw["sign"].to_owned();
+            ars.push(AccurateRipUnit::new_eac(version, sign, matching));
+        }
+
+        for ar_m_raw in AR_MISMATCH.captures_iter(&self.raw) {
+            let (Ok(version), Ok(matching)) = (ar_m_raw["version"].parse::<u8>(), ar_m_raw["cm"].parse::<u32>()) else {
+                continue;
+            };
+            let sign = ar_m_raw["sign"].to_owned();
+            let offset_sign = ar_m_raw["off_sign"].to_owned();
+            ars.push(AccurateRipUnit::new_eac_mismatch(version, sign, offset_sign, matching));
+        }
+
+        if ars.is_empty() {
+            ars.push(AccurateRipUnit::new_disabled());
+        }
+
+        ars
+    }
+}
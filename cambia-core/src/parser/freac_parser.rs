@@ -0,0 +1,310 @@
+use std::str::FromStr;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{extract::{Extractor, Gap, Quartet, ReadMode, Ripper, TrackExtractor}, integrity::IntegrityChecker, toc::{Toc, TocEntry, TocRaw}, track::{AccurateRipConfidence, AccurateRipOffset, AccurateRipStatus, AccurateRipUnit, TestAndCopy, TrackEntry, TrackError, TrackErrorData}, translate::{Translator, TranslatorCombined}, util::Time};
+use simple_text_decode::DecodedText;
+
+use super::{Parser, ParserCombined, ParsedLogCombined, ParserTrack};
+
+lazy_static! {
+    static ref RIPPER_VERSION: Regex = Regex::new(r"fre:ac v?(?P<version>[0-9][a-zA-Z0-9.\-]*)").unwrap();
+    static ref USED_DRIVE: Regex = RegexBuilder::new(r"^\s*(Using|Ripping with) drive:?\s*(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref READ_OFFSET_CORRECTION: Regex = RegexBuilder::new(r"^\s*Read offset:\s*(?P<value>[+-]?\d+)").multi_line(true).build().unwrap();
+    static ref RIPPING_MODE: Regex = RegexBuilder::new(r"^\s*(Ripping|Paranoia) mode:\s*(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref JITTER_CORRECTION: Regex = RegexBuilder::new(r"^\s*Jitter correction:\s*(?P<value>\w+)").multi_line(true).build().unwrap();
+    static ref ENCODER: Regex = RegexBuilder::new(r"^\s*(Selected )?[Ee]ncoder:\s*(?P<value>.+)$").multi_line(true).build().unwrap();
+
+    static ref TRACKS: Regex = RegexBuilder::new(r"^\s*(Reading|Ripping) track (?P<value>\d+)").multi_line(true).build().unwrap();
+    static ref TRACK_NUMBER: Regex = Regex::new(r"(Reading|Ripping) track (?P<value>\d+)").unwrap();
+    static ref SECTORS: Regex = Regex::new(r"[Ss]ectors:?\s*(?P<start>\d+)\s*-\s*(?P<end>\d+)").unwrap();
+    static ref FILENAME: Regex = RegexBuilder::new(r"^\s*(Output file|Writing to|Filename):\s*(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref EXTRACTION_SPEED: Regex = Regex::new(r"[Ss]peed:\s*(?P<value>[0-9.]+)\s*[xX]").unwrap();
+    static ref COPY_CRC: Regex = Regex::new(r"CRC32:\s*(?P<value>[A-Fa-f0-9]{8})").unwrap();
+    static ref READ_ERRORS: Regex = Regex::new(r"(Read|Uncorrected) errors:\s*(?P<value>\d+)").unwrap();
+    static ref CORRECTED_ERRORS: Regex = Regex::new(r"(Corrected|Jitter) errors:\s*(?P<value>\d+)").unwrap();
+    static ref ABORTED: Regex = Regex::new(r"(?i)(conversion|ripping) (aborted|cancelled|failed)").unwrap();
+
+    static ref AR_RESULT: Regex = Regex::new(r"AccurateRip( v(?P<version>\d))?:\s*(?P<result>Accurate|Inaccurate|Not accurate|Not found|Not in database)( \(confidence (?P<cm>\d+)\))?(\s*\[?(?P<sign>[A-Fa-f0-9]{8})\]?)?").unwrap();
+}
+
+pub struct FreacParser {
+    encoded_log: DecodedText,
+}
+
+struct FreacParserSingle {
+    log: String,
+    language: String,
+}
+
+struct FreacParserTrack {
+    raw: String,
+}
+
+impl FreacParser {
+    pub fn new(encoded_log: DecodedText) -> FreacParser {
+        FreacParser {
+            encoded_log,
+        }
+    }
+}
+
+impl FreacParserSingle {
+    pub fn new(log: String) -> FreacParserSingle {
+        let (language, log) = FreacParserSingle::translate(log);
+        FreacParserSingle {
+            log,
+            language,
+        }
+    }
+
+    fn string_match(&self, regex: &Regex) -> Option<String> {
+        regex.captures(&self.log).map(|c| c.name("value").unwrap().as_str().trim().to_string())
+    }
+
+    fn split_tracks(&self) -> Vec<&str> {
+        let starts: Vec<usize> = TRACKS.find_iter(&self.log).map(|m| m.start()).collect();
+
+        starts.iter()
+            .enumerate()
+            .map(|(idx, start)| self.log[*start..*starts.get(idx + 1).unwrap_or(&self.log.len())].trim())
+            .collect()
+    }
+}
+
+impl ParserCombined for FreacParser {
+    fn parse_combined(&self) -> ParsedLogCombined {
+        // detect::split_logs hands over one conversion at a time
+        ParsedLogCombined {
+            parsed_logs: vec![FreacParserSingle::new(self.encoded_log.text.trim().to_string()).parse()],
+            encoding: self.encoded_log.orig_encoding.to_string()
+        }
+    }
+}
+
+impl TranslatorCombined for FreacParser {
+    fn translate_combined(&self) -> String {
+        self.encoded_log.text.clone()
+    }
+}
+
+impl Parser for FreacParserSingle {}
+
+impl Extractor for FreacParserSingle {
+    fn extract_ripper(&self) -> Ripper {
+        Ripper::FreAc
+    }
+
+    fn extract_ripper_version(&self) -> String {
+        let captures = RIPPER_VERSION.captures(&self.log);
+        match captures {
+            Some(captures) => captures.name("version").unwrap().as_str().to_string(),
+            None => String::from("Unknown"),
+        }
+    }
+
+    fn extract_language(&self) -> String {
+        self.language.clone()
+    }
+
+    fn extract_read_offset(&self) -> Option<i16> {
+        self.string_match(&READ_OFFSET_CORRECTION).and_then(|v| v.parse::<i16>().ok())
+    }
+
+    fn extract_drive(&self) -> String {
+        self.string_match(&USED_DRIVE).unwrap_or_default()
+    }
+
+    // Jitter correction is fre:ac's take on accurate stream
+    fn extract_accurate_stream(&self) -> Quartet {
+        match self.string_match(&JITTER_CORRECTION) {
+            Some(value) => match value.to_ascii_lowercase().as_str() {
+                "enabled" | "on" | "yes" => Quartet::True,
+                "disabled" | "off" | "no" => Quartet::False,
+                _ => Quartet::Unknown,
+            },
+            None => Quartet::Unknown,
+        }
+    }
+
+    fn extract_use_null_samples(&self) -> Quartet {
+        Quartet::True
+    }
+
+    fn extract_test_and_copy(&self) -> Quartet {
+        Quartet::False
+    }
+
+    /*
+    Only full paranoia verifies every sector, the lighter modes skip
+    verification and/or scratch repair
+    */
+    fn extract_read_mode(&self) -> ReadMode {
+        match self.string_match(&RIPPING_MODE) {
+            Some(value) => match value.to_ascii_lowercase() {
+                full if full.contains("full") => ReadMode::Paranoid,
+                off if off.contains("disabled") || off.contains("off") || off.contains("burst") => ReadMode::Burst,
+                paranoia if paranoia.contains("paranoia") || paranoia.contains("overlap") => ReadMode::Fast,
+                _ => ReadMode::Unknown,
+            },
+            None => ReadMode::Unknown,
+        }
+    }
+
+    fn extract_gap_handling(&self) -> Gap {
+        Gap::Unknown
+    }
+
+    fn extract_toc(&self) -> Toc {
+        let mut entries: Vec<TocEntry> = Vec::new();
+
+        for raw_track in self.split_tracks() {
+            let num = match TRACK_NUMBER.captures(raw_track) {
+                Some(c) => c.name("value").unwrap().as_str().parse::<u32>().unwrap_or_default(),
+                None => continue,
+            };
+
+            if let Some(c) = SECTORS.captures(raw_track) {
+                let start_sector: u32 = c.name("start").unwrap().as_str().parse().unwrap_or_default();
+                let end_sector: u32 = c.name("end").unwrap().as_str().parse().unwrap_or_default();
+                entries.push(TocEntry::new(
+                    num,
+                    Time::from_ss(&(start_sector as f64 / 75.0).to_string()),
+                    Time::from_ss(&((end_sector + 1).saturating_sub(start_sector) as f64 / 75.0).to_string()),
+                    start_sector,
+                    end_sector,
+                ));
+            }
+        }
+
+        Toc::new(TocRaw::new(entries))
+    }
+
+    fn extract_tracks(&self) -> Vec<TrackEntry> {
+        self.split_tracks()
+            .into_iter()
+            .map(|raw_track| FreacParserTrack::new(raw_track.to_owned()).parse_track())
+            .collect()
+    }
+
+    fn extract_audio_encoder(&self) -> Vec<String> {
+        let encoder = match self.string_match(&ENCODER) {
+            Some(encoder) => encoder,
+            None => return Vec::new(),
+        };
+
+        let format = match encoder.to_ascii_lowercase() {
+            flac if flac.contains("flac") => "flac",
+            mp3 if mp3.contains("lame") || mp3.contains("mp3") => "mp3",
+            opus if opus.contains("opus") => "opus",
+            vorbis if vorbis.contains("vorbis") => "ogg",
+            aac if aac.contains("aac") || aac.contains("fdk") => "m4a",
+            wav if wav.contains("wav") || wav.contains("wave") => "wav",
+            ape if ape.contains("monkey") => "ape",
+            wv if wv.contains("wavpack") => "wv",
+            other => return vec![other],
+        };
+
+        vec![format.to_string()]
+    }
+}
+
+impl Translator for FreacParserSingle {
+    fn translate(log: String) -> (String, String) {
+        (String::from("English"), log)
+    }
+}
+
+// fre:ac does not sign its logs
+impl IntegrityChecker for FreacParserSingle {}
+
+impl ParserTrack for FreacParserTrack {}
+
+impl FreacParserTrack {
+    fn new(raw: String) -> Self {
+        FreacParserTrack { raw }
+    }
+
+    fn string_match(&self, regex: &Regex) -> String {
+        match regex.captures(&self.raw) {
+            Some(val) => val.name("value").unwrap().as_str().trim().to_string(),
+            None => String::default(),
+        }
+    }
+
+    fn optional_match<T: FromStr>(&self, regex: &Regex) -> Option<T> {
+        regex.captures(&self.raw).and_then(|val| {
+            val.name("value").and_then(|v| v.as_str().trim().parse::<T>().ok())
+        })
+    }
+}
+
+impl TrackExtractor for FreacParserTrack {
+    fn extract_num(&self) -> u8 {
+        self.string_match(&TRACK_NUMBER).parse::<u8>().unwrap_or_default()
+    }
+
+    fn extract_is_range(&self) -> bool {
+        false
+    }
+
+    fn extract_is_aborted(&self) -> bool {
+        ABORTED.is_match(&self.raw)
+    }
+
+    fn extract_filenames(&self) -> Vec<String> {
+        let filename = self.string_match(&FILENAME);
+        if filename.is_empty() { Vec::new() } else { vec![filename] }
+    }
+
+    fn extract_extraction_speed(&self) -> Option<f64> {
+        self.optional_match(&EXTRACTION_SPEED)
+    }
+
+    fn extract_test_and_copy(&self) -> TestAndCopy {
+        TestAndCopy::new_no_skipzero(String::default(), self.string_match(&COPY_CRC).to_uppercase())
+    }
+
+    // Errors paranoia managed to correct surface as jitter, the rest as read errors
+    fn extract_errors(&self) -> TrackError {
+        TrackError::new_eac(
+            TrackErrorData::new_from_count(self.optional_match(&READ_ERRORS).unwrap_or_default()),
+            TrackErrorData::new_from_count(self.optional_match(&CORRECTED_ERRORS).unwrap_or_default()),
+        )
+    }
+
+    fn extract_ar_info(&self) -> Vec<AccurateRipUnit> {
+        let mut ars: Vec<AccurateRipUnit> = Vec::new();
+
+        for captures in AR_RESULT.captures_iter(&self.raw) {
+            let version = captures.name("version").and_then(|v| v.as_str().parse::<u8>().ok());
+            let sign = captures.name("sign").map_or(String::default(), |s| s.as_str().to_uppercase());
+            let matching = captures.name("cm").and_then(|m| m.as_str().parse::<u32>().ok());
+
+            let ar = match captures.name("result").unwrap().as_str() {
+                "Accurate" => AccurateRipUnit::new(
+                    version,
+                    sign.clone(),
+                    sign,
+                    Some(AccurateRipConfidence::new(matching, None, AccurateRipOffset::Same)),
+                    AccurateRipStatus::Match,
+                ),
+                "Not found" | "Not in database" => AccurateRipUnit::new(version, sign, String::default(), None, AccurateRipStatus::NotFound),
+                _ => AccurateRipUnit::new(
+                    version,
+                    sign,
+                    String::default(),
+                    Some(AccurateRipConfidence::new(matching, None, AccurateRipOffset::Different(None))),
+                    AccurateRipStatus::Mismatch,
+                ),
+            };
+            ars.push(ar);
+        }
+
+        if ars.is_empty() {
+            ars.push(AccurateRipUnit::new_disabled());
+        }
+
+        ars
+    }
+}
@@ -0,0 +1,243 @@
+use indexmap::IndexMap;
+use regex::{Regex, RegexBuilder};
+
+use crate::{extract::{Extractor, Gap, Quartet, ReadMode, ReleaseInfo, Ripper}, integrity::IntegrityChecker, toc::Toc, track::TrackEntry, translate::{Translator, TranslatorCombined}};
+use simple_text_decode::DecodedText;
+
+use super::whipper_parser::{whipper_toc, whipper_tracks, whipper_yaml::{WhipperAccurateRipEntry, WhipperTocEntry, WhipperTrackEntry}};
+use super::{Parser, ParsedLog, ParserCombined, ParsedLogCombined};
+
+lazy_static! {
+    static ref RIPPER_VERSION: Regex = Regex::new(r"Logfile created by: morituri (?P<version>\S+)").unwrap();
+    static ref ALBUM: Regex = RegexBuilder::new(r"^Album: (?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref DRIVE: Regex = RegexBuilder::new(r"^Drive: vendor (?P<vendor>.*), model (?P<model>.*)$").multi_line(true).build().unwrap();
+    static ref READ_OFFSET_CORRECTION: Regex = RegexBuilder::new(r"^Read offset correction: (?P<value>[+-]?\d+)").multi_line(true).build().unwrap();
+    static ref WHITESPACE: Regex = Regex::new(r"\s+").unwrap();
+
+    // i.e. "        1  |      0 - 00:00:00 |  17620 - 03:54:70"
+    static ref TOC: Regex = RegexBuilder::new(r"^\s+(?P<track>\d+)\s+\|\s+(?P<start>\d+) - [0-9:]+\s+\|\s+(?P<length>\d+) - [0-9:]+\s*$").multi_line(true).build().unwrap();
+
+    static ref TRACKS: Regex = RegexBuilder::new(r"^Track\s+\d+\s*$").multi_line(true).build().unwrap();
+    static ref TRACK_NUMBER: Regex = Regex::new(r"^Track\s+(?P<value>\d+)").unwrap();
+    static ref FILENAME: Regex = RegexBuilder::new(r"^\s+Filename (?P<value>.+)$").multi_line(true).build().unwrap();
+    // Pre-gaps are printed as MM:SS:FF in frames
+    static ref PREGAP: Regex = Regex::new(r"Pre-gap: (?P<mm>\d+):(?P<ss>\d{2}):(?P<ff>\d{2})").unwrap();
+    static ref PEAK_LEVEL: Regex = Regex::new(r"Peak level (?P<value>[0-9.]+) %").unwrap();
+    static ref EXTRACTION_SPEED: Regex = Regex::new(r"Extraction Speed \(Copy\) (?P<value>[0-9.]+) X").unwrap();
+    static ref TEST_CRC: Regex = Regex::new(r"Test CRC (?P<value>[0-9A-F]{8})").unwrap();
+    static ref COPY_CRC: Regex = Regex::new(r"Copy CRC (?P<value>[0-9A-F]{8})").unwrap();
+    static ref AR_FOUND: Regex = Regex::new(r"Accurately ripped \(confidence (?P<cm>\d+)\) \[(?P<crc>[0-9A-F]{8})\]").unwrap();
+    static ref AR_MISMATCH: Regex = Regex::new(r"(?i)(not matched in|cannot be verified).*?(\(confidence (?P<cm>\d+)\))?\s*\[(?P<crc>[0-9A-F]{8})\]").unwrap();
+    static ref AR_NOT_FOUND: Regex = Regex::new(r"(?i)not present in (the )?AccurateRip database").unwrap();
+}
+
+pub struct MorituriParser {
+    encoded_log: DecodedText,
+}
+
+// morituri writes an EAC-like text log rather than whipper's YAML
+// The TOC and track sections are mapped onto whipper's entries, the conversions from there on are shared
+struct MorituriParserSingle {
+    language: String,
+    album: Option<String>,
+    drive: String,
+    read_offset: Option<i16>,
+    ripper_version: String,
+    ar_summary: String,
+    toc: IndexMap<u32, WhipperTocEntry>,
+    tracks: IndexMap<usize, WhipperTrackEntry>,
+}
+
+impl MorituriParser {
+    pub fn new(encoded_log: DecodedText) -> MorituriParser {
+        MorituriParser {
+            encoded_log,
+        }
+    }
+}
+
+impl MorituriParserSingle {
+    pub fn new(log: String) -> MorituriParserSingle {
+        let (language, log) = MorituriParserSingle::translate(log);
+
+        let drive = match DRIVE.captures(&log) {
+            Some(captures) => WHITESPACE.replace_all(&format!("{} {}", &captures["vendor"], &captures["model"]), " ").trim().to_owned(),
+            None => String::default(),
+        };
+
+        // morituri has no summary, a missing disc is reported on every track
+        let ar_summary = match AR_NOT_FOUND.find(&log) {
+            Some(m) if !AR_FOUND.is_match(&log) => m.as_str().to_owned(),
+            _ => String::default(),
+        };
+
+        MorituriParserSingle {
+            language,
+            album: ALBUM.captures(&log).map(|c| c["value"].trim().to_owned()),
+            drive,
+            read_offset: READ_OFFSET_CORRECTION.captures(&log).and_then(|c| c["value"].parse::<i16>().ok()),
+            ripper_version: RIPPER_VERSION.captures(&log).map_or_else(|| String::from("Unknown"), |c| c["version"].to_owned()),
+            ar_summary,
+            toc: MorituriParserSingle::parse_toc(&log),
+            tracks: MorituriParserSingle::parse_tracks(&log),
+        }
+    }
+
+    fn parse_toc(log: &str) -> IndexMap<u32, WhipperTocEntry> {
+        TOC.captures_iter(log)
+            .filter_map(|captures| {
+                let track = captures["track"].parse::<u32>().ok()?;
+                let start_sector = captures["start"].parse::<u32>().ok()?;
+                let length = captures["length"].parse::<u32>().ok()?;
+
+                Some((track, WhipperTocEntry {
+                    start: MorituriParserSingle::sectors_to_mm_ss(start_sector),
+                    length: MorituriParserSingle::sectors_to_mm_ss(length),
+                    start_sector,
+                    end_sector: (start_sector + length).saturating_sub(1),
+                }))
+            })
+            .collect()
+    }
+
+    fn parse_tracks(log: &str) -> IndexMap<usize, WhipperTrackEntry> {
+        let starts: Vec<usize> = TRACKS.find_iter(log).map(|m| m.start()).collect();
+
+        starts.iter()
+            .enumerate()
+            .filter_map(|(idx, start)| {
+                let raw = &log[*start..*starts.get(idx + 1).unwrap_or(&log.len())];
+                let num = TRACK_NUMBER.captures(raw)?["value"].parse::<usize>().ok()?;
+                let field = |regex: &Regex| regex.captures(raw).map_or_else(String::default, |c| c["value"].trim().to_owned());
+
+                Some((num, WhipperTrackEntry {
+                    filename: field(&FILENAME),
+                    pregap: PREGAP.captures(raw).map(|c| MorituriParserSingle::msf_to_mm_ss_cs(&c["mm"], &c["ss"], &c["ff"])),
+                    // Peak is a percentage in morituri and a ratio in whipper
+                    peak_level: field(&PEAK_LEVEL).parse::<f64>().map(|p| p / 100.0).unwrap_or_default(),
+                    preemphasis: None,
+                    extraction_speed: field(&EXTRACTION_SPEED),
+                    extraction_quality: String::default(),
+                    test_crc: field(&TEST_CRC),
+                    copy_crc: field(&COPY_CRC),
+                    status: String::default(),
+                    // morituri only knows AccurateRip v1
+                    ar_v1: MorituriParserSingle::ar_entry(raw),
+                    ar_v2: None,
+                }))
+            })
+            .collect()
+    }
+
+    fn ar_entry(raw: &str) -> Option<WhipperAccurateRipEntry> {
+        let (result, captures) = match AR_FOUND.captures(raw) {
+            Some(captures) => ("Found, exact match", captures),
+            None => ("Found, NO exact match", AR_MISMATCH.captures(raw)?),
+        };
+
+        Some(WhipperAccurateRipEntry {
+            result: result.to_owned(),
+            confidence: captures.name("cm").and_then(|cm| cm.as_str().parse::<u32>().ok()),
+            local_crc: captures["crc"].to_owned(),
+            remote_crc: None,
+        })
+    }
+
+    fn sectors_to_mm_ss(sectors: u32) -> String {
+        format!("{}:{:05.2}", sectors / (75 * 60), (sectors % (75 * 60)) as f64 / 75.0)
+    }
+
+    fn msf_to_mm_ss_cs(mm: &str, ss: &str, ff: &str) -> String {
+        let frames = ff.parse::<u32>().unwrap_or_default();
+        format!("{}:{}:{:02}", mm, ss, frames * 100 / 75)
+    }
+}
+
+impl ParserCombined for MorituriParser {
+    fn parse_combined(&self) -> ParsedLogCombined {
+        let parsed_logs: Vec<ParsedLog> = vec![MorituriParserSingle::new(self.encoded_log.text.trim().to_string()).parse()];
+
+        ParsedLogCombined {
+            parsed_logs,
+            encoding: self.encoded_log.orig_encoding.to_string()
+        }
+    }
+}
+
+impl TranslatorCombined for MorituriParser {
+    fn translate_combined(&self) -> String {
+        self.encoded_log.text.clone()
+    }
+}
+
+impl Parser for MorituriParserSingle {}
+
+impl Extractor for MorituriParserSingle {
+    fn extract_ripper(&self) -> Ripper {
+        Ripper::Morituri
+    }
+
+    fn extract_ripper_version(&self) -> String {
+        self.ripper_version.clone()
+    }
+
+    fn extract_release_info(&self) -> ReleaseInfo {
+        match self.album.as_ref().and_then(|r| r.split_once(" - ")) {
+            Some(s) => ReleaseInfo::new(s.0.trim().to_owned(), s.1.trim().to_owned()),
+            None => ReleaseInfo::default(),
+        }
+    }
+
+    fn extract_language(&self) -> String {
+        self.language.clone()
+    }
+
+    fn extract_read_offset(&self) -> Option<i16> {
+        self.read_offset
+    }
+
+    fn extract_drive(&self) -> String {
+        self.drive.clone()
+    }
+
+    // Like whipper, morituri relies on cdparanoia
+    fn extract_accurate_stream(&self) -> Quartet {
+        Quartet::True
+    }
+
+    fn extract_use_null_samples(&self) -> Quartet {
+        Quartet::True
+    }
+
+    fn extract_test_and_copy(&self) -> Quartet {
+        match self.tracks.values().any(|track| !track.test_crc.is_empty()) {
+            true => Quartet::True,
+            false => Quartet::False,
+        }
+    }
+
+    fn extract_read_mode(&self) -> ReadMode {
+        ReadMode::Secure
+    }
+
+    fn extract_gap_handling(&self) -> Gap {
+        Gap::Append
+    }
+
+    fn extract_toc(&self) -> Toc {
+        whipper_toc(&self.toc)
+    }
+
+    fn extract_tracks(&self) -> Vec<TrackEntry> {
+        whipper_tracks(&self.tracks, &self.ar_summary)
+    }
+}
+
+impl Translator for MorituriParserSingle {
+    fn translate(log: String) -> (String, String) {
+        (String::from("English"), log)
+    }
+}
+
+// morituri logs carry no checksum
+impl IntegrityChecker for MorituriParserSingle {}
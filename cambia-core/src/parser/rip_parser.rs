@@ -0,0 +1,336 @@
+use std::str::FromStr;
+
+use regex::{Regex, RegexBuilder};
+
+use crate::{extract::{Extractor, Quartet, ReadMode, ReleaseInfo, Ripper, TrackExtractor}, integrity::IntegrityChecker, toc::{Toc, TocEntry, TocRaw}, track::{AccurateRipConfidence, AccurateRipConfidenceTotal, AccurateRipOffset, AccurateRipStatus, AccurateRipUnit, TestAndCopy, TrackEntry, TrackError, TrackErrorData}, translate::{Translator, TranslatorCombined}, util::Time};
+use simple_text_decode::DecodedText;
+
+use super::{Parser, ParsedLog, ParserCombined, ParsedLogCombined, ParserTrack};
+
+lazy_static! {
+    static ref RIPPER_VERSION: Regex = Regex::new(r"Rip (?P<version>[0-9.]+)( \(\d+\))? Audio Extraction Log").unwrap();
+    static ref USED_DRIVE: Regex = RegexBuilder::new(r"^\s*Drive( name)?:\s*(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref READ_OFFSET_CORRECTION: Regex = RegexBuilder::new(r"^\s*Read offset( correction)?:\s*(?P<value>[+-]?\d+)").multi_line(true).build().unwrap();
+    static ref ALBUM_TITLE: Regex = RegexBuilder::new(r"^\s*(Album )?Title:\s*(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref ALBUM_ARTIST: Regex = RegexBuilder::new(r"^\s*(Album )?Artist:\s*(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref USE_C2: Regex = RegexBuilder::new(r"^\s*Use C2( error pointers)?:\s*(?P<boolean>Yes|No)").multi_line(true).case_insensitive(true).build().unwrap();
+    static ref READ_MODE: Regex = RegexBuilder::new(r"^\s*(Extraction|Read) mode:\s*(?P<value>\w+)").multi_line(true).build().unwrap();
+
+    static ref TRACKS: Regex = RegexBuilder::new(r"^Track \d+:?\s*$").multi_line(true).build().unwrap();
+    static ref TRACK_NUMBER: Regex = Regex::new(r"^Track (?P<value>\d+)").unwrap();
+    static ref SECTORS: Regex = Regex::new(r"Sectors:\s*(?P<start>\d+)\s*-\s*(?P<end>\d+)").unwrap();
+    static ref FILENAME: Regex = RegexBuilder::new(r"^\s*(File|Filename):\s*(?P<value>.+)$").multi_line(true).build().unwrap();
+    static ref PEAK_LEVEL: Regex = Regex::new(r"Peak( level)?:\s*(?P<value>[0-9.]+)\s*(?P<percent>%)?").unwrap();
+    static ref EXTRACTION_SPEED: Regex = Regex::new(r"Extraction speed:\s*(?P<value>[0-9.]+)\s*[xX]").unwrap();
+    static ref PREGAP: Regex = Regex::new(r"Pre-gap( length)?:\s*(?P<value>\d+) sectors").unwrap();
+    static ref TEST_CRC: Regex = Regex::new(r"Test CRC:\s*(?P<value>[A-Fa-f0-9]{8})").unwrap();
+    static ref COPY_CRC: Regex = Regex::new(r"Copy CRC:\s*(?P<value>[A-Fa-f0-9]{8})").unwrap();
+    static ref READ_ERRORS: Regex = Regex::new(r"(Read|C2) errors:\s*(?P<value>\d+)").unwrap();
+    static ref RETRIES: Regex = Regex::new(r"(Re-read|Retried) sectors:\s*(?P<value>\d+)").unwrap();
+    static ref ABORTED: Regex = Regex::new(r"(?i)extraction (aborted|failed|cancelled)").unwrap();
+
+    static ref AR_RESULT: Regex = Regex::new(r"AccurateRip( v(?P<version>\d))?:\s*(?P<result>Verified|Accurately ripped|Not verified|Mismatch|Not found|Not present)( \(confidence (?P<cm>\d+)(/(?P<ct>\d+))?\))?(\s*\[?(?P<sign>[A-Fa-f0-9]{8})\]?)?").unwrap();
+}
+
+pub struct RipParser {
+    encoded_log: DecodedText,
+}
+
+struct RipParserSingle {
+    log: String,
+    language: String,
+}
+
+struct RipParserTrack {
+    raw: String,
+}
+
+impl RipParser {
+    pub fn new(encoded_log: DecodedText) -> RipParser {
+        RipParser {
+            encoded_log,
+        }
+    }
+}
+
+impl RipParserSingle {
+    pub fn new(log: String) -> RipParserSingle {
+        let (language, log) = RipParserSingle::translate(log);
+        RipParserSingle {
+            log,
+            language,
+        }
+    }
+
+    fn string_match(&self, regex: &Regex) -> Option<String> {
+        regex.captures(&self.log).map(|c| c.name("value").unwrap().as_str().trim().to_string())
+    }
+
+    // Release level fields are everything before the first track
+    fn header(&self) -> &str {
+        match TRACKS.find(&self.log) {
+            Some(m) => &self.log[..m.start()],
+            None => &self.log,
+        }
+    }
+
+    fn split_tracks(&self) -> Vec<&str> {
+        let starts: Vec<usize> = TRACKS.find_iter(&self.log).map(|m| m.start()).collect();
+
+        starts.iter()
+            .enumerate()
+            .map(|(idx, start)| self.log[*start..*starts.get(idx + 1).unwrap_or(&self.log.len())].trim())
+            .collect()
+    }
+}
+
+impl ParserCombined for RipParser {
+    fn parse_combined(&self) -> ParsedLogCombined {
+        let parsed_logs: Vec<ParsedLog> = vec![RipParserSingle::new(self.encoded_log.text.trim().to_string()).parse()];
+
+        ParsedLogCombined {
+            parsed_logs,
+            encoding: self.encoded_log.orig_encoding.to_string()
+        }
+    }
+}
+
+impl TranslatorCombined for RipParser {
+    fn translate_combined(&self) -> String {
+        self.encoded_log.text.clone()
+    }
+}
+
+impl Parser for RipParserSingle {}
+
+impl Extractor for RipParserSingle {
+    fn extract_ripper(&self) -> Ripper {
+        Ripper::Rip
+    }
+
+    fn extract_ripper_version(&self) -> String {
+        let captures = RIPPER_VERSION.captures(&self.log);
+        match captures {
+            Some(captures) => captures.name("version").unwrap().as_str().to_string(),
+            None => String::from("Unknown"),
+        }
+    }
+
+    fn extract_release_info(&self) -> ReleaseInfo {
+        let header = self.header();
+        let artist = ALBUM_ARTIST.captures(header).map(|c| c.name("value").unwrap().as_str().trim().to_owned());
+        let title = ALBUM_TITLE.captures(header).map(|c| c.name("value").unwrap().as_str().trim().to_owned());
+
+        match (artist, title) {
+            (Some(artist), Some(title)) => ReleaseInfo::new(artist, title),
+            _ => ReleaseInfo::default(),
+        }
+    }
+
+    fn extract_language(&self) -> String {
+        self.language.clone()
+    }
+
+    fn extract_read_offset(&self) -> Option<i16> {
+        self.string_match(&READ_OFFSET_CORRECTION).and_then(|v| v.parse::<i16>().ok())
+    }
+
+    fn extract_drive(&self) -> String {
+        self.string_match(&USED_DRIVE).unwrap_or_default()
+    }
+
+    // Rip compares every sector against a second read, the stream is always accurate
+    fn extract_accurate_stream(&self) -> Quartet {
+        Quartet::True
+    }
+
+    fn extract_use_c2(&self) -> Quartet {
+        match USE_C2.captures(&self.log) {
+            Some(captures) => match captures.name("boolean").unwrap().as_str().to_ascii_lowercase().as_str() {
+                "yes" => Quartet::True,
+                _ => Quartet::False,
+            },
+            None => Quartet::Unknown,
+        }
+    }
+
+    fn extract_use_null_samples(&self) -> Quartet {
+        Quartet::True
+    }
+
+    fn extract_test_and_copy(&self) -> Quartet {
+        match TEST_CRC.is_match(&self.log) {
+            true => Quartet::True,
+            false => Quartet::False,
+        }
+    }
+
+    fn extract_read_mode(&self) -> ReadMode {
+        match self.string_match(&READ_MODE) {
+            Some(value) => match value.to_ascii_lowercase().as_str() {
+                "secure" | "comparison" => ReadMode::Secure,
+                "burst" => ReadMode::Burst,
+                _ => ReadMode::Unknown,
+            },
+            None => ReadMode::Secure,
+        }
+    }
+
+    fn extract_toc(&self) -> Toc {
+        let mut entries: Vec<TocEntry> = Vec::new();
+
+        for raw_track in self.split_tracks() {
+            let num = match TRACK_NUMBER.captures(raw_track) {
+                Some(c) => c.name("value").unwrap().as_str().parse::<u32>().unwrap_or_default(),
+                None => continue,
+            };
+
+            if let Some(c) = SECTORS.captures(raw_track) {
+                let start_sector: u32 = c.name("start").unwrap().as_str().parse().unwrap_or_default();
+                let end_sector: u32 = c.name("end").unwrap().as_str().parse().unwrap_or_default();
+                entries.push(TocEntry::new(
+                    num,
+                    Time::from_ss(&(start_sector as f64 / 75.0).to_string()),
+                    Time::from_ss(&((end_sector + 1).saturating_sub(start_sector) as f64 / 75.0).to_string()),
+                    start_sector,
+                    end_sector,
+                ));
+            }
+        }
+
+        Toc::new(TocRaw::new(entries))
+    }
+
+    fn extract_tracks(&self) -> Vec<TrackEntry> {
+        self.split_tracks()
+            .into_iter()
+            .map(|raw_track| RipParserTrack::new(raw_track.to_owned()).parse_track())
+            .collect()
+    }
+
+    fn extract_audio_encoder(&self) -> Vec<String> {
+        let mut encoders: Vec<String> = Vec::new();
+        for raw_track in self.split_tracks() {
+            if let Some(ext) = FILENAME.captures(raw_track).and_then(|c| c.name("value").unwrap().as_str().trim().rsplit_once('.').map(|(_, e)| e.to_ascii_lowercase())) {
+                if !encoders.contains(&ext) {
+                    encoders.push(ext);
+                }
+            }
+        }
+        encoders
+    }
+}
+
+impl Translator for RipParserSingle {
+    fn translate(log: String) -> (String, String) {
+        (String::from("English"), log)
+    }
+}
+
+// Rip does not sign its logs
+impl IntegrityChecker for RipParserSingle {}
+
+impl ParserTrack for RipParserTrack {}
+
+impl RipParserTrack {
+    fn new(raw: String) -> Self {
+        RipParserTrack { raw }
+    }
+
+    fn string_match(&self, regex: &Regex) -> String {
+        match regex.captures(&self.raw) {
+            Some(val) => val.name("value").unwrap().as_str().trim().to_string(),
+            None => String::default(),
+        }
+    }
+
+    fn optional_match<T: FromStr>(&self, regex: &Regex) -> Option<T> {
+        regex.captures(&self.raw).and_then(|val| {
+            val.name("value").and_then(|v| v.as_str().trim().parse::<T>().ok())
+        })
+    }
+}
+
+impl TrackExtractor for RipParserTrack {
+    fn extract_num(&self) -> u8 {
+        self.string_match(&TRACK_NUMBER).parse::<u8>().unwrap_or_default()
+    }
+
+    fn extract_is_range(&self) -> bool {
+        false
+    }
+
+    fn extract_is_aborted(&self) -> bool {
+        ABORTED.is_match(&self.raw)
+    }
+
+    fn extract_filenames(&self) -> Vec<String> {
+        let filename = self.string_match(&FILENAME);
+        if filename.is_empty() { Vec::new() } else { vec![filename] }
+    }
+
+    fn extract_peak_level(&self) -> Option<f64> {
+        let captures = PEAK_LEVEL.captures(&self.raw)?;
+        let value = captures.name("value").unwrap().as_str().parse::<f64>().ok()?;
+        match captures.name("percent") {
+            Some(_) => Some(value / 100.0),
+            None => Some(value),
+        }
+    }
+
+    fn extract_pregap_length(&self) -> Option<Time> {
+        self.optional_match::<u32>(&PREGAP).map(|sectors| Time::from_ss(&(sectors as f64 / 75.0).to_string()))
+    }
+
+    fn extract_extraction_speed(&self) -> Option<f64> {
+        self.optional_match(&EXTRACTION_SPEED)
+    }
+
+    fn extract_test_and_copy(&self) -> TestAndCopy {
+        TestAndCopy::new_no_skipzero(self.string_match(&TEST_CRC).to_uppercase(), self.string_match(&COPY_CRC).to_uppercase())
+    }
+
+    fn extract_errors(&self) -> TrackError {
+        // Sectors that needed re-reading to match are recoverable, hence jitter-like
+        TrackError::new_eac(
+            TrackErrorData::new_from_count(self.optional_match(&READ_ERRORS).unwrap_or_default()),
+            TrackErrorData::new_from_count(self.optional_match(&RETRIES).unwrap_or_default()),
+        )
+    }
+
+    fn extract_ar_info(&self) -> Vec<AccurateRipUnit> {
+        let mut ars: Vec<AccurateRipUnit> = Vec::new();
+
+        for captures in AR_RESULT.captures_iter(&self.raw) {
+            let version = captures.name("version").and_then(|v| v.as_str().parse::<u8>().ok());
+            let sign = captures.name("sign").map_or(String::default(), |s| s.as_str().to_uppercase());
+            let matching = captures.name("cm").and_then(|m| m.as_str().parse::<u32>().ok());
+            let total = captures.name("ct").and_then(|t| t.as_str().parse::<u32>().ok()).map(AccurateRipConfidenceTotal::All);
+
+            let ar = match captures.name("result").unwrap().as_str() {
+                "Verified" | "Accurately ripped" => AccurateRipUnit::new(
+                    version,
+                    sign.clone(),
+                    sign,
+                    Some(AccurateRipConfidence::new(matching, total, AccurateRipOffset::Same)),
+                    AccurateRipStatus::Match,
+                ),
+                "Not found" | "Not present" => AccurateRipUnit::new(version, sign, String::default(), None, AccurateRipStatus::NotFound),
+                _ => AccurateRipUnit::new(
+                    version,
+                    sign,
+                    String::default(),
+                    Some(AccurateRipConfidence::new(matching, total, AccurateRipOffset::Different(None))),
+                    AccurateRipStatus::Mismatch,
+                ),
+            };
+            ars.push(ar);
+        }
+
+        if ars.is_empty() {
+            ars.push(AccurateRipUnit::new_disabled());
+        }
+
+        ars
+    }
+}
@@ -1,12 +1,14 @@
-mod whipper_yaml;
+pub(crate) mod whipper_yaml;
 
 use regex::Regex;
 use sha2::{Sha256, Digest};
 
-use crate::{extract::{Extractor, Gap, MediaType, Quartet, ReadMode, ReleaseInfo, Ripper, TrackExtractor}, integrity::IntegrityChecker, toc::{Toc, TocEntry, TocRaw}, track::{TestAndCopy, TrackEntry}, translate::{Translator, TranslatorCombined}, util::Time};
+use crate::{anomaly::{Anomaly, AnomalyKind}, extract::{Extractor, Gap, MediaType, Quartet, ReadMode, ReleaseInfo, Ripper, TrackExtractor}, integrity::IntegrityChecker, toc::{Toc, TocEntry, TocRaw}, track::{AccurateRipConfidence, AccurateRipOffset, AccurateRipStatus, AccurateRipUnit, TestAndCopy, TrackEntry}, translate::{Translator, TranslatorCombined}, util::Time};
 use simple_text_decode::DecodedText;
 
-use self::whipper_yaml::{WhipperLogYaml, WhipperTrackEntry, ReleaseInfoUnion};
+use indexmap::IndexMap;
+
+use self::whipper_yaml::{WhipperAccurateRipEntry, WhipperLogYaml, WhipperTocEntry, WhipperTrackEntry, ReleaseInfoUnion};
 
 use super::{Parser, ParsedLog, ParserCombined, ParsedLogCombined, ParserTrack};
 
@@ -15,6 +17,8 @@ lazy_static! {
     static ref CHECKSUM: Regex = Regex::new(r"\nSHA-256 hash: [a-zA-Z0-9]{64}").unwrap();
     
     static ref SANITISE_RELEASE: Regex = Regex::new(r"(Release|Album): (.+)").unwrap();
+    static ref AR_SUMMARY_NOT_FOUND: Regex = Regex::new(r"(?i)not (present|found) in (the )?AccurateRip database").unwrap();
+    static ref HEALTH_STATUS_OK: Regex = Regex::new(r"(?i)^No errors occurred").unwrap();
 }
 
 pub struct WhipperParser {
@@ -29,6 +33,7 @@ struct WhipperParserSingle {
 
 struct WhipperParserTrack<'a> {
     num: u8,
+    ar_not_found: bool,
     yaml: &'a WhipperTrackEntry,
 }
 
@@ -69,8 +74,31 @@ impl WhipperParserSingle {
 }
 
 impl<'a> WhipperParserTrack<'a> {
-    fn new(num: u8, yaml: &'a WhipperTrackEntry) -> Self {
-        Self { num, yaml }
+    fn new(num: u8, ar_not_found: bool, yaml: &'a WhipperTrackEntry) -> Self {
+        Self { num, ar_not_found, yaml }
+    }
+
+    fn ar_unit(version: u8, entry: &WhipperAccurateRipEntry) -> AccurateRipUnit {
+        let sign = entry.local_crc.to_uppercase();
+        let offset_sign = entry.remote_crc.clone().unwrap_or_default().to_uppercase();
+
+        match entry.result.as_str() {
+            "Found, exact match" => AccurateRipUnit::new(
+                Some(version),
+                sign,
+                offset_sign,
+                Some(AccurateRipConfidence::new(entry.confidence, None, AccurateRipOffset::Same)),
+                AccurateRipStatus::Match,
+            ),
+            found if found.starts_with("Found") => AccurateRipUnit::new(
+                Some(version),
+                sign,
+                offset_sign,
+                Some(AccurateRipConfidence::new(entry.confidence, None, AccurateRipOffset::Different(None))),
+                AccurateRipStatus::Mismatch,
+            ),
+            _ => AccurateRipUnit::new(Some(version), sign, offset_sign, None, AccurateRipStatus::NotFound),
+        }
     }
 }
 
@@ -168,32 +196,64 @@ impl Extractor for WhipperParserSingle {
     }
 
     fn extract_toc(&self) -> Toc {
-        let mut entries: Vec<TocEntry> = Vec::new();
-
-        for (k, v) in &self.yaml.toc {
-            entries.push(TocEntry::new(
-                *k,
-                Time::from_mm_ss(&v.start),
-                Time::from_mm_ss(&v.length),
-                v.start_sector,
-                v.end_sector,
+        whipper_toc(&self.yaml.toc)
+    }
+
+    // The status report is whipper's own verdict, it is written last so a missing EOF means the log was cut short
+    fn extract_anomalies(&self) -> Vec<Anomaly> {
+        let status_report = &self.yaml.status_report;
+        let mut anomalies: Vec<Anomaly> = Vec::new();
+
+        if !status_report.health_status.is_empty() && !HEALTH_STATUS_OK.is_match(&status_report.health_status) {
+            anomalies.push(Anomaly::new_release(
+                AnomalyKind::RipperReportedErrors,
+                format!("whipper reported: {}", status_report.health_status),
+            ));
+        }
+        if status_report.eof.is_empty() {
+            anomalies.push(Anomaly::new_release(
+                AnomalyKind::Truncated,
+                String::from("Log ends before the end of whipper's status report"),
             ));
         }
 
-        entries.sort_by_key(|e| e.track);
-
-        Toc::new(TocRaw::new(entries))
+        anomalies
     }
 
     fn extract_tracks(&self) -> Vec<TrackEntry> {
-        let mut tracks: Vec<TrackEntry> = Vec::new();
-        
-        for (num, track) in &self.yaml.tracks {
-            tracks.push(WhipperParserTrack::new(num.to_owned().try_into().unwrap_or_default(), track).parse_track());
-        }
+        whipper_tracks(&self.yaml.tracks, &self.yaml.status_report.ar_summary)
+    }
+}
+
+// Shared with morituri, whose TOC and track sections carry the same fields
+pub(crate) fn whipper_toc(toc: &IndexMap<u32, WhipperTocEntry>) -> Toc {
+    let mut entries: Vec<TocEntry> = Vec::new();
+
+    for (k, v) in toc {
+        entries.push(TocEntry::new(
+            *k,
+            Time::from_mm_ss(&v.start),
+            Time::from_mm_ss(&v.length),
+            v.start_sector,
+            v.end_sector,
+        ));
+    }
+
+    entries.sort_by_key(|e| e.track);
+
+    Toc::new(TocRaw::new(entries))
+}
 
-        tracks
+// The AccurateRip summary decides what tracks without any AccurateRip blocks stand for
+pub(crate) fn whipper_tracks(tracks: &IndexMap<usize, WhipperTrackEntry>, ar_summary: &str) -> Vec<TrackEntry> {
+    let mut entries: Vec<TrackEntry> = Vec::new();
+    let ar_not_found = AR_SUMMARY_NOT_FOUND.is_match(ar_summary);
+
+    for (num, track) in tracks {
+        entries.push(WhipperParserTrack::new(num.to_owned().try_into().unwrap_or_default(), ar_not_found, track).parse_track());
     }
+
+    entries
 }
 
 impl Translator for WhipperParserSingle {
@@ -260,4 +320,21 @@ impl<'a> TrackExtractor for WhipperParserTrack<'a> {
     fn extract_test_and_copy(&self) -> TestAndCopy {
         TestAndCopy::new_no_skipzero(self.yaml.test_crc.clone(), self.yaml.copy_crc.clone())
     }
+
+    fn extract_ar_info(&self) -> Vec<AccurateRipUnit> {
+        let mut ars: Vec<AccurateRipUnit> = Vec::new();
+
+        if let Some(ar_v1) = &self.yaml.ar_v1 {
+            ars.push(WhipperParserTrack::ar_unit(1, ar_v1));
+        }
+        if let Some(ar_v2) = &self.yaml.ar_v2 {
+            ars.push(WhipperParserTrack::ar_unit(2, ar_v2));
+        }
+
+        if ars.is_empty() {
+            ars.push(if self.ar_not_found { AccurateRipUnit::new_eac_notfound() } else { AccurateRipUnit::new_disabled() });
+        }
+
+        ars
+    }
 }
\ No newline at end of file
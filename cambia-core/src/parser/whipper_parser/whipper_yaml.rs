@@ -81,6 +81,18 @@ pub struct WhipperTocEntry {
     pub end_sector: u32,
 }
 
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct WhipperAccurateRipEntry {
+    #[serde(rename = "Result")]
+    pub result: String,
+    #[serde(default, rename = "Confidence")]
+    pub confidence: Option<u32>,
+    #[serde(default, rename = "Local CRC")]
+    pub local_crc: String,
+    #[serde(default, rename = "Remote CRC")]
+    pub remote_crc: Option<String>,
+}
+
 #[derive(Serialize, Deserialize, Debug)]
 pub struct WhipperTrackEntry {
     #[serde(rename = "Filename")]
@@ -101,7 +113,20 @@ pub struct WhipperTrackEntry {
     pub copy_crc: String,
     #[serde(rename = "Status")]
     pub status: String,
-    // FIXME: AccurateRip missing
+    #[serde(default, rename = "AccurateRip v1")]
+    pub ar_v1: Option<WhipperAccurateRipEntry>,
+    #[serde(default, rename = "AccurateRip v2")]
+    pub ar_v2: Option<WhipperAccurateRipEntry>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+pub struct WhipperStatusReport {
+    #[serde(default, rename = "AccurateRip summary")]
+    pub ar_summary: String,
+    #[serde(default, rename = "Health status")]
+    pub health_status: String,
+    #[serde(default, rename = "EOF")]
+    pub eof: String,
 }
 
 #[derive(Serialize, Deserialize, Debug)]
@@ -118,6 +143,8 @@ pub struct WhipperLogYaml {
     pub toc: IndexMap<u32, WhipperTocEntry>,
     #[serde(rename = "Tracks")]
     pub tracks: IndexMap<usize, WhipperTrackEntry>,
+    #[serde(default, rename = "Conclusive status report")]
+    pub status_report: WhipperStatusReport,
     #[serde(default, rename = "SHA-256 hash")]
     pub checksum: String,
 }
@@ -145,6 +172,7 @@ impl Default for WhipperLogYaml {
             },
             toc: IndexMap::new(),
             tracks: IndexMap::new(),
+            status_report: WhipperStatusReport::default(),
             checksum: String::from("Unknown")
         }
     }
@@ -26,6 +26,8 @@ lazy_static! {
 
     static ref READ_OFFSET_CORRECTION: Regex = Regex::new(r"Read offset correction( *): ([+-]?[0-9]+)").unwrap();
     static ref GAP_HANDLING: Regex = Regex::new(r"Gap status( *): (.+)").unwrap();
+    static ref MAX_RETRY_COUNT: Regex = Regex::new(r"Max retry count\s*:\s*(?P<value>\d+)").unwrap();
+    static ref ALBUM_GAIN: Regex = Regex::new(r"All Tracks\s+Album gain\s*:\s*(?P<value>[+-]?\d+(\.\d+)?) dB").unwrap();
 
     static ref TEST_AND_COPY: Regex = Regex::new(r"CRC32 hash \(test run\)(\s*:) ([0-9A-F]{8})").unwrap();
 
@@ -35,7 +37,6 @@ lazy_static! {
     static ref TRACKS: Regex = RegexBuilder::new(r"^Track \d+\s+(Filename|Pre-gap length)").multi_line(true).build().unwrap();
     static ref LOG_EOF: Regex = Regex::new(r"((No|Some) (errors|inconsistencies) (occurred|found)\s+)?End of status report").unwrap();
 
-    // TODO: Some track fields that don't affect scoring were skipped
     // FIXME: There could be CRLF issues due to how regex crate dot_matches_new_line works
     // FIXME: This will definitely miss unusual encoders
     static ref TRACK_NUMBER: Regex = Regex::new(r"Track\s*(?P<value>\d+)").unwrap();
@@ -45,7 +46,10 @@ lazy_static! {
     static ref PEAK_LEVEL: Regex = Regex::new(r"Peak(\s*):(\s*)(?P<value>\d+\.\d+)").unwrap();
     static ref TEST_CRC: Regex = Regex::new(r"CRC32 hash \(test run\)(\s*):(\s*)(?P<value>[A-F0-9]{8})").unwrap();
     static ref COPY_CRC: Regex = Regex::new(r"CRC32 hash(\s*):(\s*)(?P<value>[A-F0-9]{8})").unwrap();
-    static ref ERROR: Regex = Regex::new(r"(?P<type>Read error|Skipped \(treated as error\)|Damaged sector count|Inconsistency in error sectors|Missing samples|((Jitter error|Edge jitter error|Atom jitter error|Drift error|Dropped bytes error|Duplicated bytes error) \(maybe fixed\)))((\s*):(\s*)(?P<count>\d+))?").unwrap();
+    static ref TEST_SKIPZERO_CRC: Regex = Regex::new(r"CRC32 hash \(test run, skip zero\)(\s*):(\s*)(?P<value>[A-F0-9]{8})").unwrap();
+    static ref COPY_SKIPZERO_CRC: Regex = Regex::new(r"CRC32 hash \(skip zero\)(\s*):(\s*)(?P<value>[A-F0-9]{8})").unwrap();
+    static ref TRACK_GAIN: Regex = Regex::new(r"Track gain(\s*):(\s*)(?P<value>[+-]?\d+(\.\d+)?) dB").unwrap();
+    static ref ERROR: Regex = Regex::new(r"(?P<type>Read error|Skipped \(treated as error\)|Damaged sector count|Inconsistency in error sectors|Retry sector count|Missing samples|((Jitter error|Edge jitter error|Atom jitter error|Drift error|Dropped bytes error|Duplicated bytes error) \(maybe fixed\)))((\s*):(\s*)(?P<count>\d+))?").unwrap();
     static ref DAMAGED_SECTORS: Regex =  Regex::new(r"List of damaged sector positions\s*:(?:\s*\(\d+\)\s*\d+:\d+:\d+)+").unwrap();
     static ref SUSPICIOUS_POSITIONS: Regex = Regex::new(r"List of suspicious positions\s*:(?:\s*\(\d+\)\s*\d+:\d+:\d+)+").unwrap();
     static ref ERROR_TIME: Regex = Regex::new(r"\s*\(\d+\)\s*(?P<time>\d+:\d+:\d+)").unwrap();
@@ -253,6 +257,16 @@ impl Extractor for XldParserSingle {
         }
     }
 
+    fn extract_max_retry_count(&self) -> Option<u32> {
+        let captures = MAX_RETRY_COUNT.captures(&self.translated_log);
+        captures.and_then(|captures| captures.name("value").unwrap().as_str().parse::<u32>().ok())
+    }
+
+    fn extract_album_gain(&self) -> Option<f64> {
+        let captures = ALBUM_GAIN.captures(&self.translated_log);
+        captures.and_then(|captures| captures.name("value").unwrap().as_str().parse::<f64>().ok())
+    }
+
     fn extract_audio_encoder(&self) -> Vec<String> {
         // No use checking all the tracks since this setting seems to be global for all the tracks
         let captures = FILENAME_MULTI.captures(&self.translated_log);
@@ -434,19 +448,25 @@ impl TrackExtractor for XldParserTrack {
         captures.map(|captures| Time::from_mm_ss_cs(captures.name("time").unwrap().as_str()))
     }
 
+    fn extract_gain(&self) -> Option<f64> {
+        self.optional_match::<f64>(&TRACK_GAIN)
+    }
+
     fn extract_test_and_copy(&self) -> TestAndCopy {
         let test_crc = self.string_match(&TEST_CRC);
         let copy_crc = self.string_match(&COPY_CRC);
+        // Logs without the test run hash keep the skip-zero integrity unknown
+        let test_skipzero_crc = self.string_match(&TEST_SKIPZERO_CRC);
+        let copy_skipzero_crc = self.string_match(&COPY_SKIPZERO_CRC);
 
-        // TODO: Redundant but parse and add skipzero hashes
-        TestAndCopy::new_no_skipzero(test_crc, copy_crc)
+        TestAndCopy::new(test_crc, copy_crc, test_skipzero_crc, copy_skipzero_crc)
     }
 
     // TODO: Feels kind of ugly
     fn extract_errors(&self) -> TrackError {
         let captures_all = ERROR.captures_iter(&self.raw);
 
-        let (mut r_c, mut s_c, mut drf_c, mut drp_c, mut dup_c, mut dmg_c, mut inc_c) = (0_u32, 0_u32, 0_u32, 0_u32, 0_u32, 0_u32, 0_u32);
+        let (mut r_c, mut s_c, mut drf_c, mut drp_c, mut dup_c, mut dmg_c, mut inc_c, mut rty_c) = (0_u32, 0_u32, 0_u32, 0_u32, 0_u32, 0_u32, 0_u32, 0_u32);
         let (mut jg_c, mut je_c, mut ja_c) = (0_u32, 0_u32, 0_u32);
         let mut m_s = false;
 
@@ -468,6 +488,7 @@ impl TrackExtractor for XldParserTrack {
                 "Dropped bytes error (maybe fixed)" => { drp_c = count },
                 "Duplicated bytes error (maybe fixed)" => { dup_c = count },
                 "Inconsistency in error sectors" => { inc_c = count },
+                "Retry sector count" => { rty_c = count },
                 "Missing samples" => { m_s = true },
                 _ => {}
             }
@@ -505,7 +526,7 @@ impl TrackExtractor for XldParserTrack {
         }
         let inc_d = TrackErrorData::new(inc_c, inc_r);
 
-        TrackError::new_xld(r_c, s_c, jg_c, je_c, ja_c, drf_c, drp_c, dup_c, dmg_d, inc_d, m_s)
+        TrackError::new_xld(r_c, s_c, jg_c, je_c, ja_c, drf_c, drp_c, dup_c, rty_c, dmg_d, inc_d, m_s)
     }
 
     fn extract_ar_info(&self) -> Vec<AccurateRipUnit> {
@@ -0,0 +1,142 @@
+use serde::{Serialize, Deserialize};
+use ts_rs::TS;
+
+use crate::{drive::{DriveMatchQuality, DriveUtils}, evaluate::{Evaluation, EvaluationCombined, EvaluationUnit, EvaluationUnitField, EvaluationUnitScope, Score}, extract::Ripper, parser::{ParsedLog, ParsedLogCombined}};
+
+#[derive(Serialize, Deserialize, TS, Clone, Copy, PartialEq, Debug)]
+#[ts(export)]
+pub enum RemediationAction {
+    // The setting affects the extracted audio
+    ReRip,
+    // The audio is fine, only the log has to be produced again
+    RegenerateLog,
+}
+
+#[derive(Serialize, Deserialize, TS, Clone, PartialEq, Debug)]
+#[ts(export)]
+pub struct Remediation {
+    pub setting: String,
+    pub location: String,
+    pub value: String,
+    pub action: RemediationAction,
+}
+
+impl Remediation {
+    fn new(setting: &str, location: &str, value: &str, action: RemediationAction) -> Self {
+        Remediation {
+            setting: setting.to_owned(),
+            location: location.to_owned(),
+            value: value.to_owned(),
+            action,
+        }
+    }
+}
+
+// Fills in the remediations of every evaluation, evaluations are in the same order as the parsed logs
+pub fn remediate(parsed_logs: &ParsedLogCombined, evaluation_combined: &mut [EvaluationCombined]) {
+    for combined in evaluation_combined.iter_mut() {
+        for (parsed_log, evaluation) in parsed_logs.parsed_logs.iter().zip(combined.evaluations.iter_mut()) {
+            evaluation.remediations = evaluation.evaluation_units.iter()
+                .map(|unit| remediation_for(parsed_log, unit))
+                .collect();
+            evaluation.fixed_score = fixed_score(evaluation);
+        }
+    }
+}
+
+// Only settings have a remediation, damage to the disc or the log itself does not
+// Track level deductions are always about the disc or the files
+pub fn remediation_for(parsed_log: &ParsedLog, unit: &EvaluationUnit) -> Option<Remediation> {
+    if unit.data.scope != EvaluationUnitScope::Release {
+        return None;
+    }
+
+    match parsed_log.ripper {
+        Ripper::EAC => eac_remediation(parsed_log, &unit.data.field),
+        Ripper::XLD => xld_remediation(parsed_log, &unit.data.field),
+        Ripper::Whipper => whipper_remediation(parsed_log, &unit.data.field),
+        _ => None,
+    }
+}
+
+// Score the log would get once every deduction with a remediation is gone, only for numeric scores
+// A failed evaluation never got to the other deductions, so there is nothing to add up
+fn fixed_score(evaluation: &Evaluation) -> Option<Score> {
+    if evaluation.failed {
+        return None;
+    }
+    evaluation.score.numeric()?;
+    let recovered: Score = evaluation.evaluation_units.iter()
+        .zip(evaluation.remediations.iter())
+        .filter(|(_, remediation)| remediation.is_some())
+        .map(|(unit, _)| unit.unit_score)
+        .sum();
+
+    Some(evaluation.score + recovered)
+}
+
+fn drive_offset(parsed_log: &ParsedLog) -> String {
+    match DriveUtils::fuzzy_search_model(parsed_log.drive.clone()) {
+        DriveMatchQuality::STRONG(Some(offset)) => format!("{:+}", offset),
+        _ => String::from("The drive's offset in the AccurateRip database"),
+    }
+}
+
+fn is_virtual_drive(parsed_log: &ParsedLog) -> bool {
+    let drive = parsed_log.drive.to_lowercase();
+    drive.contains("generic dvd-rom scsi cdrom device") || drive.contains("(null) (null)")
+}
+
+fn eac_remediation(parsed_log: &ParsedLog, field: &EvaluationUnitField) -> Option<Remediation> {
+    let remediation = match field {
+        EvaluationUnitField::Drive if is_virtual_drive(parsed_log) => Remediation::new("Drive", "Drive selection in the main window", "A physical drive", RemediationAction::ReRip),
+        EvaluationUnitField::RipperVersion => Remediation::new("EAC version", "Help → Check for updates", "0.99 or newer", RemediationAction::ReRip),
+        EvaluationUnitField::Offset => Remediation::new("Use read sample offset correction", "EAC → Drive Options → Offset / Speed", &drive_offset(parsed_log), RemediationAction::ReRip),
+        EvaluationUnitField::Cache => Remediation::new("Drive caches audio data", "EAC → Drive Options → Extraction Method", "Enabled", RemediationAction::ReRip),
+        EvaluationUnitField::AccurateStream => Remediation::new("Drive has 'Accurate Stream' feature", "EAC → Drive Options → Extraction Method", "Enabled", RemediationAction::ReRip),
+        EvaluationUnitField::C2 => Remediation::new("Drive is capable of retrieving C2 error information", "EAC → Drive Options → Extraction Method", "Disabled", RemediationAction::ReRip),
+        EvaluationUnitField::ReadMode => Remediation::new("Secure mode with following drive features", "EAC → Drive Options → Extraction Method", "Selected", RemediationAction::ReRip),
+        EvaluationUnitField::TestAndCopy => Remediation::new("Test & Copy", "Action → Test & Copy Selected Tracks", "Used", RemediationAction::ReRip),
+        EvaluationUnitField::Samples => Remediation::new("Fill up missing offset samples with silence", "EAC → EAC Options → Extraction", "Enabled", RemediationAction::ReRip),
+        EvaluationUnitField::SilentBlocks => Remediation::new("Delete leading and trailing silent blocks", "EAC → EAC Options → Extraction", "Disabled", RemediationAction::ReRip),
+        EvaluationUnitField::NullSamples => Remediation::new("Use null samples in CRC calculations", "EAC → EAC Options → Extraction", "Enabled", RemediationAction::RegenerateLog),
+        EvaluationUnitField::Normalization => Remediation::new("Normalize to", "EAC → EAC Options → Normalize", "Disabled", RemediationAction::ReRip),
+        EvaluationUnitField::Gap => Remediation::new("Gap handling", "Action → Detect Gaps, then Action → Copy Image & CUE Sheet → Multiple WAV Files With Gaps (Noncompliant)", "Appended to previous track", RemediationAction::ReRip),
+        EvaluationUnitField::RangeSplit => Remediation::new("Copy mode", "Action → Copy Selected Tracks", "Track by track copy, not a range", RemediationAction::ReRip),
+        EvaluationUnitField::Encoder => Remediation::new("Compression format", "EAC → Compression Options → External Compression", "A lossless encoder (e.g. FLAC)", RemediationAction::ReRip),
+        EvaluationUnitField::Tag => Remediation::new("Add ID3 tag", "EAC → Compression Options → External Compression", "Disabled", RemediationAction::RegenerateLog),
+        EvaluationUnitField::Checksum => Remediation::new("Append checksum to status report", "EAC → EAC Options → Tools", "Enabled", RemediationAction::RegenerateLog),
+        _ => return None,
+    };
+
+    Some(remediation)
+}
+
+fn xld_remediation(parsed_log: &ParsedLog, field: &EvaluationUnitField) -> Option<Remediation> {
+    let remediation = match field {
+        EvaluationUnitField::Drive if is_virtual_drive(parsed_log) => Remediation::new("Drive", "CD drive selected for ripping", "A physical drive", RemediationAction::ReRip),
+        EvaluationUnitField::RipperVersion | EvaluationUnitField::Checksum => Remediation::new("XLD version", "Download the current XLD release", "20121222 or newer", RemediationAction::ReRip),
+        EvaluationUnitField::Offset => Remediation::new("Read offset correction value", "XLD → Preferences → CD Rip", &drive_offset(parsed_log), RemediationAction::ReRip),
+        EvaluationUnitField::Cache => Remediation::new("Disable audio cache", "XLD → Preferences → CD Rip", "Enabled", RemediationAction::ReRip),
+        EvaluationUnitField::C2 => Remediation::new("Use C2 Error Pointers", "XLD → Preferences → CD Rip", "Disabled", RemediationAction::ReRip),
+        EvaluationUnitField::ReadMode => Remediation::new("Ripper mode", "XLD → Preferences → CD Rip", "XLD Secure Ripper", RemediationAction::ReRip),
+        EvaluationUnitField::MaxRetryCount => Remediation::new("Max retry count", "XLD → Preferences → CD Rip", "10 or higher", RemediationAction::ReRip),
+        EvaluationUnitField::TestAndCopy => Remediation::new("Test before copy", "XLD → Preferences → CD Rip", "Enabled", RemediationAction::ReRip),
+        EvaluationUnitField::Gap => Remediation::new("Gap status", "XLD → Preferences → CD Rip", "Analyzed, Appended", RemediationAction::ReRip),
+        EvaluationUnitField::Encoder => Remediation::new("Output format", "XLD → Preferences → General", "A lossless format (e.g. FLAC)", RemediationAction::ReRip),
+        _ => return None,
+    };
+
+    Some(remediation)
+}
+
+fn whipper_remediation(parsed_log: &ParsedLog, field: &EvaluationUnitField) -> Option<Remediation> {
+    let remediation = match field {
+        EvaluationUnitField::RipperVersion => Remediation::new("whipper version", "Package manager or the whipper repository", "0.7.3 or newer", RemediationAction::ReRip),
+        EvaluationUnitField::Offset => Remediation::new("read_offset", "whipper offset find, or the drive section of whipper.conf", &drive_offset(parsed_log), RemediationAction::ReRip),
+        EvaluationUnitField::Cache => Remediation::new("defeats_cache", "whipper drive analyze, or the drive section of whipper.conf", "True", RemediationAction::ReRip),
+        _ => return None,
+    };
+
+    Some(remediation)
+}
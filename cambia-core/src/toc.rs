@@ -7,6 +7,21 @@ use urlencoding::encode;
 
 use crate::util::Time;
 
+// A session gap holds the previous session's lead-out and the next session's lead-in
+// The usual gap is 11400 sectors (6750 lead-out, 4500 lead-in, 150 pregap), anything shorter than a lead-in stays in the session
+static SESSION_GAP_MIN: u32 = 4500;
+// MusicBrainz places the audio lead-out of a multi-session disc this far before the next session
+static MBZ_SESSION_GAP: u32 = 11400;
+// A mixed mode data track is followed by at least a 2 second gap before the first audio track
+static DATA_AUDIO_GAP_MIN: u32 = 150;
+
+#[derive(Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Debug, TS)]
+#[ts(export)]
+pub enum TrackType {
+    Audio,
+    Data,
+}
+
 #[derive(Serialize, Deserialize, Clone, Copy, TS)]
 #[ts(export)]
 pub struct TocEntry {
@@ -15,14 +30,24 @@ pub struct TocEntry {
     pub length: Time,
     pub start_sector: u32,
     pub end_sector: u32,
+    pub track_type: TrackType,
+    // Sessions are numbered from 1
+    pub session: u32,
 }
 
+// Audio CDs have a single audio session
+// Mixed mode discs start with a data track in the same session, the TOC can't tell so it's marked from the ripped tracks
+// Enhanced CDs (CD-Extra) have their data tracks in a later session, found through the gap in front of it
 #[derive(Serialize, Deserialize, Clone, Default, TS)]
 #[ts(export)]
 pub struct TocRaw {
     pub entries: Vec<TocEntry>,
+    // End of the first session, where the audio of an enhanced CD stops
     pub lead_out: u32,
     pub data_tracks: u32,
+    pub sessions: u32,
+    // End of the last session
+    pub disc_lead_out: u32,
 }
 
 #[derive(Serialize, Deserialize, TS)]
@@ -54,27 +79,63 @@ impl TocEntry {
             length,
             start_sector,
             end_sector,
+            track_type: TrackType::Audio,
+            session: 1,
         }
     }
+
+    pub fn is_audio(&self) -> bool {
+        self.track_type == TrackType::Audio
+    }
 }
 
 impl TocRaw {
-    pub fn new(entries: Vec<TocEntry>) -> TocRaw {
+    // Tracks already marked as data stay data, every track after a session gap becomes data
+    pub fn new(mut entries: Vec<TocEntry>) -> TocRaw {
+        if assign_sessions(&mut entries).is_err() {
+            return TocRaw::default();
+        }
 
-        let data_tracks = get_data_tracks(&entries);
+        let first_session_end = entries.iter().filter(|entry| entry.session == 1).map(|entry| entry.end_sector).max().unwrap_or_default();
 
-        match data_tracks {
-            Ok(val) => {
-                let lead_out = entries[entries.len().saturating_sub(val as usize).saturating_sub(1)].end_sector + 1;
+        TocRaw {
+            lead_out: first_session_end + 1,
+            data_tracks: entries.iter().filter(|entry| !entry.is_audio()).count() as u32,
+            sessions: entries.last().map_or(0, |entry| entry.session),
+            disc_lead_out: entries.last().map_or(0, |entry| entry.end_sector + 1),
+            entries,
+        }
+    }
 
-                TocRaw {
-                    entries,
-                    lead_out,
-                    data_tracks: val,
-                }
-            }
-            Err(_) => TocRaw::default(),
-        } 
+    pub fn audio_entries(&self) -> Vec<TocEntry> {
+        self.entries.iter().filter(|entry| entry.is_audio()).copied().collect()
+    }
+
+    pub fn first_session_entries(&self) -> Vec<TocEntry> {
+        self.entries.iter().filter(|entry| entry.session == 1).copied().collect()
+    }
+
+    // Rippers skip the data track of a mixed mode disc, so it's the only first session track that wasn't ripped
+    // Returns whether the first track was marked
+    pub fn mark_leading_data(&mut self, ripped: &[u32]) -> bool {
+        let session = self.first_session_entries();
+        let Some((first, rest)) = session.split_first() else {
+            return false;
+        };
+
+        if rest.is_empty() || !first.is_audio() || ripped.contains(&first.track) || !rest.iter().all(|entry| ripped.contains(&entry.track)) {
+            return false;
+        }
+
+        // Without the gap it's an audio track that was left out of the rip
+        if rest[0].start_sector.saturating_sub(first.end_sector + 1) < DATA_AUDIO_GAP_MIN {
+            return false;
+        }
+
+        let mut entries = self.entries.clone();
+        entries[0].track_type = TrackType::Data;
+        *self = TocRaw::new(entries);
+        true
     }
 }
 
@@ -111,22 +172,24 @@ impl Default for Toc {
     }
 }
 
+// Tracks of the first session, data tracks in front included
+// Multi-session discs get a lead-out 11400 sectors before the second session, as libdiscid does
 pub fn raw_to_mbz(toc_raw: TocRaw) -> TocHash {
-    let mut offsets: Vec<TocEntry> = toc_raw.entries.clone();
+    let offsets: Vec<TocEntry> = toc_raw.first_session_entries();
 
     if offsets.is_empty() {
         return TocHash::default();
     }
 
-    if toc_raw.data_tracks > 0 {
-        offsets.truncate(offsets.len().saturating_sub(toc_raw.data_tracks as usize));
-    }
+    let lead_out = match toc_raw.entries.iter().find(|entry| entry.session > 1) {
+        Some(next_session) => next_session.start_sector.saturating_sub(MBZ_SESSION_GAP),
+        None => toc_raw.lead_out,
+    };
 
     let mut sb = format!("{:02X}{:02X}{:08X}",
                                 offsets[0].track,
-                                // TODO: Not sure if it should use the last audio track or include the data tracks
                                 offsets.last().copied().unwrap().track,
-                                toc_raw.lead_out + 150);
+                                lead_out + 150);
     // TODO: Loop can probably be avoided
     for idx in 0..99 {
         if idx < offsets.len() {
@@ -146,7 +209,7 @@ pub fn raw_to_mbz(toc_raw: TocRaw) -> TocHash {
     let url_param: String = format!("{} {} {} {}",
                                     offsets[0].track,
                                     offsets.last().copied().unwrap().track,
-                                    (toc_raw.lead_out + 150),
+                                    (lead_out + 150),
                                     offsets.iter().map(|offset| (offset.start_sector + 150).to_string()).collect::<Vec<String>>().join(" ")
                                 );
 
@@ -155,24 +218,22 @@ pub fn raw_to_mbz(toc_raw: TocRaw) -> TocHash {
     
 }
 
+// Audio tracks only, relative to the first audio track and ending with the last one (CUETools)
 pub fn raw_to_ctdb_tocid(toc_raw: TocRaw) -> TocHash {
-    let mut entries: Vec<TocEntry> = toc_raw.entries.clone();
+    let entries: Vec<TocEntry> = toc_raw.audio_entries();
 
     if entries.is_empty() {
         return TocHash::default();
     }
 
-    if toc_raw.data_tracks > 0 {
-        entries.truncate(entries.len().saturating_sub(toc_raw.data_tracks as usize));
-    }
-
     let pregap = entries[0].start_sector;
+    let audio_end = entries.last().unwrap().end_sector + 1;
     let mut sb: String = String::new();
 
     for entry in entries.iter().skip(1) {
         sb.push_str(format!("{:08X}", entry.start_sector - pregap).as_str())
     }
-    sb.push_str(format!("{:08X}", toc_raw.lead_out - pregap).as_str());
+    sb.push_str(format!("{:08X}", audio_end - pregap).as_str());
     // TODO: Loop can probably be avoided
     for _ in 0..(100_usize.saturating_sub(entries.len())) {
         sb.push_str("00000000");
@@ -190,24 +251,37 @@ pub fn raw_to_ctdb_tocid(toc_raw: TocRaw) -> TocHash {
     TocHash::new(hash, format!("http://db.cuetools.net/top.php?tocid={}", url_param))
 }
 
+// Sums over the audio tracks counted from 1, the lead-out is the end of the whole disc (CUETools)
+// Mixed mode discs start counting at their first audio track, not at track 1
 pub fn raw_to_accurip_tocid(toc_raw: TocRaw) -> TocHash {
-    if toc_raw.entries.is_empty() {
+    let entries: Vec<TocEntry> = toc_raw.audio_entries();
+
+    if entries.is_empty() {
         return TocHash::default();
     }
 
-    let mut offsets_add = toc_raw.entries.len() as u32;
-    let mut offsets_mul = max(toc_raw.entries.last().unwrap().end_sector + 1, 1) * (offsets_add + 1);
+    let mut offsets_add = toc_raw.disc_lead_out;
+    let mut offsets_mul = max(toc_raw.disc_lead_out, 1).wrapping_mul(entries.len() as u32 + 1);
 
-    for (i, entry) in toc_raw.entries.iter().enumerate() {
-        offsets_add += entry.end_sector;
-        offsets_mul += max(entry.start_sector, 1) * (i as u32 + 1);
+    for (idx, entry) in entries.iter().enumerate() {
+        offsets_add = offsets_add.wrapping_add(entry.start_sector);
+        offsets_mul = offsets_mul.wrapping_add(max(entry.start_sector, 1).wrapping_mul(idx as u32 + 1));
     }
 
     let freedb = raw_to_freedb(toc_raw.clone());
     let id = format!("{:08x}-{:08x}-{}", offsets_add, offsets_mul, freedb.hash.to_lowercase());
-    TocHash::new(id, String::default())
+    // The database is sharded by the last three nibbles of the first ID
+    let url = format!("http://www.accuraterip.com/accuraterip/{:x}/{:x}/{:x}/dBAR-{:03}-{}.bin",
+                        offsets_add & 0xF,
+                        (offsets_add >> 4) & 0xF,
+                        (offsets_add >> 8) & 0xF,
+                        entries.len(),
+                        id
+                    );
+    TocHash::new(id, url)
 }
 
+// Every track and the end of the disc, like freedb
 pub fn raw_to_gn(toc_raw: TocRaw) -> TocHash {
 
     let mut offsets: Vec<u32> = toc_raw.entries.iter().map(|toc_entry| toc_entry.start_sector).collect();
@@ -216,11 +290,7 @@ pub fn raw_to_gn(toc_raw: TocRaw) -> TocHash {
         return TocHash::default();
     }
 
-    if toc_raw.data_tracks > 0 {
-        offsets.truncate(offsets.len().saturating_sub(toc_raw.data_tracks as usize));
-    }
-
-    offsets.push(toc_raw.lead_out);
+    offsets.push(toc_raw.disc_lead_out);
 
     let pregap: u32 = if offsets[0] == 0 {
         150
@@ -233,18 +303,15 @@ pub fn raw_to_gn(toc_raw: TocRaw) -> TocHash {
     TocHash::new(offsets_str.join(" "), String::default())
 }
 
+// Media Player only sees the first session
 pub fn raw_to_mcdi(toc_raw: TocRaw) -> TocHash {
 
-    let mut offsets: Vec<u32> = toc_raw.entries.iter().map(|toc_entry| toc_entry.start_sector).collect();
+    let mut offsets: Vec<u32> = toc_raw.first_session_entries().iter().map(|toc_entry| toc_entry.start_sector).collect();
 
     if offsets.is_empty() {
         return TocHash::default();
     }
 
-    if toc_raw.data_tracks > 0 {
-        offsets.truncate(offsets.len().saturating_sub(toc_raw.data_tracks as usize));
-    }
-
     offsets.push(toc_raw.lead_out);
     
     let pregap: u32 = if offsets[0] == 0 {
@@ -259,6 +326,7 @@ pub fn raw_to_mcdi(toc_raw: TocRaw) -> TocHash {
     TocHash::new(offsets_joined.clone(), format!("https://musicmatch-ssl.xboxlive.com/cdinfo/GetMDRCD.aspx?locale=409&geoid=f4&version=12.0.17134.48&userlocale=409&CD={}", offsets_joined))
 }
 
+// Every track of every session counts, data tracks included
 pub fn raw_to_freedb(toc_raw: TocRaw) -> TocHash {
     if toc_raw.entries.is_empty() {
         return TocHash::default();
@@ -268,35 +336,38 @@ pub fn raw_to_freedb(toc_raw: TocRaw) -> TocHash {
         .map(|entry| (entry.start_sector / 75 + 2).to_string().chars().map(|c| c.to_digit(10).unwrap()).sum::<u32>())
         .sum::<u32>() % 255;
 
-    let duration = toc_raw.entries.last().unwrap().end_sector / 75;
+    let duration = toc_raw.disc_lead_out / 75 - toc_raw.entries[0].start_sector / 75;
     let tracks = toc_raw.entries.len() as u32;
 
     let id = format!("{:08X}", (checksum << 24) | ((duration) << 8) | tracks);
     TocHash::new(id.clone(), format!("https://gnudb.org/search/discid/{}", id))
 }
 
-fn get_data_tracks(entries: &Vec<TocEntry>) -> Result<u32, TocError> {
+// A gap of at least SESSION_GAP_MIN sectors is a session boundary, later sessions only hold data
+// Shorter gaps are left in the session, some rippers leave out the transition after a data track
+fn assign_sessions(entries: &mut [TocEntry]) -> Result<(), TocError> {
     if entries.is_empty() {
         return Err(TocError);
     }
 
-    let last_idx = entries.len().saturating_sub(1);
+    let mut session: u32 = 1;
 
-    for (idx, _) in entries.iter().enumerate() {
-        if idx < last_idx {
-            if entries[idx].end_sector > entries[idx + 1].start_sector {
+    for idx in 0..entries.len() {
+        if idx > 0 {
+            if entries[idx - 1].end_sector >= entries[idx].start_sector {
                 return Err(TocError);
             }
-            let gap = entries[idx + 1].start_sector - entries[idx].end_sector - 1;
-            if gap != 0 {
-                if gap == 11400 {
-                    return Ok((last_idx - idx) as u32);
-                } else {
-                    return Err(TocError);
-                }
+            let gap = entries[idx].start_sector - entries[idx - 1].end_sector - 1;
+            if gap >= SESSION_GAP_MIN {
+                session += 1;
             }
         }
+
+        entries[idx].session = session;
+        if session > 1 {
+            entries[idx].track_type = TrackType::Data;
+        }
     }
 
-    Ok(0)
-}
\ No newline at end of file
+    Ok(())
+}
@@ -182,6 +182,8 @@ pub struct TrackError {
     pub dropped: TrackErrorData,
     #[serde(skip_serializing_if = "TrackErrorData::is_default", rename = "Duplicated bytes")]
     pub duplicated: TrackErrorData,
+    #[serde(skip_serializing_if = "TrackErrorData::is_default", rename = "Retry sectors")]
+    pub retry_sectors: TrackErrorData,
     #[serde(skip_serializing_if = "TrackErrorData::is_default", rename = "Damaged sectors")]
     pub damaged_sectors: TrackErrorData,
     #[serde(skip_serializing_if = "TrackErrorData::is_default", rename = "Inconsistency in error sectors")]
@@ -215,6 +217,7 @@ impl TrackError {
             drift: TrackErrorData::default(),
             dropped: TrackErrorData::default(),
             duplicated: TrackErrorData::default(),
+            retry_sectors: TrackErrorData::default(),
             damaged_sectors: TrackErrorData::default(),
             inconsistent_err_sectors: TrackErrorData::default(),
             missing_samples: TrackErrorData::default(),
@@ -222,7 +225,7 @@ impl TrackError {
     }
 
     #[allow(clippy::too_many_arguments)]
-    pub fn new_xld(r_c: u32, s_c: u32, jg_c: u32, je_c: u32, ja_c: u32, drf_c: u32, drp_c: u32, dup_c: u32, dmg_d: TrackErrorData, inc_d: TrackErrorData, m_s: bool) -> Self {
+    pub fn new_xld(r_c: u32, s_c: u32, jg_c: u32, je_c: u32, ja_c: u32, drf_c: u32, drp_c: u32, dup_c: u32, rty_c: u32, dmg_d: TrackErrorData, inc_d: TrackErrorData, m_s: bool) -> Self {
         TrackError {
             read: TrackErrorData::new_from_count(r_c),
             skip: TrackErrorData::new_from_count(s_c),
@@ -232,6 +235,7 @@ impl TrackError {
             drift: TrackErrorData::new_from_count(drf_c),
             dropped: TrackErrorData::new_from_count(drp_c),
             duplicated: TrackErrorData::new_from_count(dup_c),
+            retry_sectors: TrackErrorData::new_from_count(rty_c),
             damaged_sectors: dmg_d,
             inconsistent_err_sectors: inc_d,
             missing_samples: TrackErrorData::new_from_bool(m_s),
@@ -33,6 +33,10 @@ impl Time {
         let cs: u64 = str::parse(split[2]).unwrap();
         Time(Duration::from_secs(m * 60) + Duration::from_secs(s) + Duration::from_millis(cs * 10))
     }
+
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0.as_secs_f64()
+    }
 }
 
 impl ops::Add<Time> for Time {
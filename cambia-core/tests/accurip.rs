@@ -0,0 +1,77 @@
+mod common;
+
+use cambia_core::{accurip::{compare, decode, track_records}, track::AccurateRipStatus};
+
+use common::{parse_single, read_fixture};
+
+// Three pressings of the disc in logs/whipper/errors.log, the first two hold its v1 and v2 CRCs
+static DBAR: &str = "accurip/dBAR-002-0000dcd4-000251a9-0d020602.bin";
+
+#[test]
+fn decode_pressings() {
+    let pressings = decode(&read_fixture(DBAR)).unwrap();
+
+    assert_eq!(pressings.len(), 3);
+    assert!(pressings.iter().all(|pressing| pressing.disc_id1 == 0xdcd4 && pressing.disc_id2 == 0x251a9 && pressing.cddb == 0x0d020602));
+    assert!(pressings.iter().all(|pressing| pressing.tracks.len() == 2));
+    assert_eq!(pressings[0].tracks[0].confidence, 12);
+    assert_eq!(pressings[0].tracks[0].crc, "1D3A55F0");
+    assert_eq!(pressings[1].tracks[1].crc, "4B1A0C9E");
+    assert_eq!(pressings[2].tracks[1].frame450_crc, "3A3B3C3D");
+
+    let records = track_records(&pressings);
+    assert_eq!(records.len(), 2);
+    assert_eq!(records[1].num, 2);
+    assert_eq!(records[1].records.iter().map(|record| record.confidence).collect::<Vec<u8>>(), vec![11, 8, 2]);
+}
+
+#[test]
+fn truncated_header() {
+    let data = read_fixture(DBAR);
+
+    assert!(decode(&data[..5]).is_err());
+    // Cut inside the second pressing's header
+    assert!(decode(&data[..31 + 5]).is_err());
+    assert_eq!(decode(&[]).unwrap(), vec![]);
+}
+
+#[test]
+fn truncated_tracks() {
+    let data = read_fixture(DBAR);
+
+    // Header complete, second track cut short
+    assert!(decode(&data[..13 + 9 + 4]).is_err());
+    // The header says two tracks, only one follows
+    assert!(decode(&data[..13 + 9]).is_err());
+    assert_eq!(decode(&data[..31]).unwrap().len(), 1);
+}
+
+#[cfg(feature = "whipper")]
+#[test]
+fn compare_with_log() {
+    let log = parse_single("logs/whipper/errors.log");
+    assert_eq!(log.toc.accurip_tocid.hash, "0000dcd4-000251a9-0d020602");
+
+    let pressings = decode(&read_fixture(DBAR)).unwrap();
+    let comparisons = compare(&pressings, &log);
+    assert_eq!(comparisons.len(), 4);
+
+    // Track 1 matches both of its CRCs
+    let track1: Vec<_> = comparisons.iter().filter(|comparison| comparison.num == 1).collect();
+    assert!(track1.iter().all(|comparison| comparison.status == AccurateRipStatus::Match));
+    assert_eq!((track1[0].matching, track1[0].total), (12, 24));
+    assert_eq!((track1[1].matching, track1[1].total), (9, 24));
+
+    // Track 2's v1 CRC only matches the third pressing, its v2 CRC matches nothing
+    let track2: Vec<_> = comparisons.iter().filter(|comparison| comparison.num == 2).collect();
+    assert_eq!(track2[0].sign, "00FF00FF");
+    assert!(track2[0].status == AccurateRipStatus::Match);
+    assert_eq!((track2[0].matching, track2[0].total), (2, 21));
+    assert_eq!(track2[1].sign, "FF00FF00");
+    assert!(track2[1].status == AccurateRipStatus::Mismatch);
+    assert_eq!(track2[1].matching, 0);
+
+    // A disc missing from the database has no records to match
+    let comparisons = compare(&pressings[..0], &log);
+    assert!(comparisons.iter().all(|comparison| comparison.status == AccurateRipStatus::NotFound));
+}
@@ -0,0 +1,34 @@
+#![allow(dead_code)]
+use std::{fs, path::PathBuf};
+
+use cambia_core::{handler::detect_rippers, parser::ParsedLog};
+use simple_text_decode::DecodedText;
+
+pub fn fixture_path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join(name)
+}
+
+pub fn read_fixture(name: &str) -> Vec<u8> {
+    fs::read(fixture_path(name)).unwrap_or_else(|e| panic!("Missing fixture {}: {}", name, e))
+}
+
+// Goes through detection like an upload does
+pub fn parse_fixture(name: &str) -> Vec<ParsedLog> {
+    parse_bytes(name, &read_fixture(name))
+}
+
+// For variations of a fixture built in the test itself
+pub fn parse_bytes(name: &str, bytes: &[u8]) -> Vec<ParsedLog> {
+    let encoded_log = DecodedText::new(bytes).unwrap_or_default();
+    detect_rippers(encoded_log)
+        .unwrap_or_else(|e| panic!("{} was not detected: {}", name, e))
+        .iter()
+        .flat_map(|parser| parser.parse_combined().parsed_logs)
+        .collect()
+}
+
+pub fn parse_single(name: &str) -> ParsedLog {
+    let mut parsed_logs = parse_fixture(name);
+    assert_eq!(parsed_logs.len(), 1, "{} should hold a single log", name);
+    parsed_logs.remove(0)
+}
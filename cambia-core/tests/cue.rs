@@ -0,0 +1,107 @@
+mod common;
+
+use cambia_core::{cue::{compare::compare, write::write_cue, CueLayout, CueSheet, CueTocError}, parser::ParsedLog};
+
+use common::parse_single;
+
+// Length of every file in a split rip, derived from where its first sector sits on the disc
+#[cfg(feature = "whipper")]
+fn file_lengths(log: &ParsedLog, layout: CueLayout) -> Vec<u32> {
+    let raw = &log.toc.raw;
+    let entries = raw.audio_entries();
+    let starts: Vec<u32> = entries.iter()
+        .enumerate()
+        .map(|(idx, entry)| match layout {
+            CueLayout::GapsPrepended if idx > 0 => {
+                let track = log.tracks.iter().find(|track| u32::from(track.num) == entry.track).unwrap();
+                let pregap = track.pregap_length.map_or(0, |time| (time.as_secs_f64() * 75.0).round() as u32);
+                entry.start_sector - pregap
+            },
+            _ => entry.start_sector,
+        })
+        .chain(std::iter::once(raw.lead_out))
+        .collect();
+
+    match layout {
+        CueLayout::SingleFile => vec![raw.lead_out - starts[0]],
+        _ => starts.windows(2).map(|pair| pair[1] - pair[0]).collect(),
+    }
+}
+
+#[cfg(feature = "whipper")]
+fn round_trip(layout: CueLayout) {
+    let log = parse_single("logs/whipper/errors.log");
+
+    let text = write_cue(&log, Some(layout)).unwrap();
+    let cue = CueSheet::parse(&text).unwrap();
+    assert_eq!(cue.layout(), layout);
+
+    let comparison = compare(&cue, &log, &file_lengths(&log, layout));
+    assert_eq!(comparison.unchecked, None);
+    assert_eq!(comparison.mismatches, vec![]);
+}
+
+#[cfg(feature = "whipper")]
+#[test]
+fn round_trip_single_file() {
+    round_trip(CueLayout::SingleFile);
+}
+
+#[cfg(feature = "whipper")]
+#[test]
+fn round_trip_gaps_appended() {
+    round_trip(CueLayout::GapsAppended);
+}
+
+#[cfg(feature = "whipper")]
+#[test]
+fn round_trip_gaps_prepended() {
+    round_trip(CueLayout::GapsPrepended);
+}
+
+#[test]
+fn prepended_positions() {
+    let cue = CueSheet::parse(concat!(
+        "FILE \"01.wav\" WAVE\n",
+        "  TRACK 01 AUDIO\n",
+        "    INDEX 01 00:00:00\n",
+        "FILE \"02.wav\" WAVE\n",
+        "  TRACK 02 AUDIO\n",
+        "    INDEX 00 00:00:00\n",
+        "    INDEX 01 00:02:00\n",
+        "FILE \"03.wav\" WAVE\n",
+        "  TRACK 03 AUDIO\n",
+        "    INDEX 01 00:00:00\n",
+    )).unwrap();
+    assert_eq!(cue.layout(), CueLayout::GapsPrepended);
+
+    let positions = cue.positions(&[1000, 2150]).unwrap();
+    assert_eq!(positions.iter().map(|position| position.start_sector).collect::<Vec<u32>>(), vec![0, 1150, 3150]);
+    assert_eq!(positions[1].indices[0].sector, 1000);
+
+    assert_eq!(cue.positions(&[1000]).err(), Some(CueTocError::MissingFileLength(String::from("02.wav"))));
+}
+
+#[test]
+fn huge_index_time_is_rejected() {
+    let cue = CueSheet::parse(concat!(
+        "FILE \"range.wav\" WAVE\n",
+        "  TRACK 01 AUDIO\n",
+        "    INDEX 01 4294967295:00:00\n",
+    ));
+    assert!(cue.is_err());
+}
+
+#[test]
+fn positions_past_u32_are_rejected() {
+    let cue = CueSheet::parse(concat!(
+        "FILE \"01.wav\" WAVE\n",
+        "  TRACK 01 AUDIO\n",
+        "    INDEX 01 00:00:00\n",
+        "FILE \"02.wav\" WAVE\n",
+        "  TRACK 02 AUDIO\n",
+        "    INDEX 01 900000:00:00\n",
+    )).unwrap();
+
+    assert_eq!(cue.positions(&[u32::MAX]).err(), Some(CueTocError::TooLong));
+}
@@ -0,0 +1,151 @@
+mod common;
+
+use cambia_core::{detect::{detect, detect_best, split_logs}, extract::Ripper};
+
+use common::{parse_bytes, read_fixture};
+
+static EAC_HEADER: &str = "Exact Audio Copy V1.6 from 23. October 2020\n\nEAC extraction logfile from 14. March 2024, 20:11\n";
+static XLD_HEADER: &str = "X Lossless Decoder version 20230916 (157.2)\n\nXLD extraction logfile from 2024-03-14 20:11:08 +0100\n";
+// One EAC track and the log checksum, without the header lines
+static EAC_BODY: &str = concat!(
+    "Track  1\n\n",
+    "     Filename C:\\Rips\\01 - Intro.wav\n\n",
+    "     Test CRC 3F2A1B4C\n",
+    "     Copy CRC 3F2A1B4C\n",
+    "     Copy OK\n\n",
+    "No errors occurred\n\n",
+    "End of status report\n\n",
+    "==== Log checksum 5E9C01A4D2B7F3086C1E4A9D7B20F5C3E8A61D4F09B7C2E5A3D8F1B6C4E07A92 ====\n",
+);
+
+fn fixture_text(name: &str) -> String {
+    String::from_utf8(read_fixture(name)).unwrap()
+}
+
+#[test]
+fn below_min_score_is_unsupported() {
+    // A lone CRC line is worth less than MIN_SCORE
+    let text = "Some notes\n     Copy CRC 3F2A1B4C\n";
+    assert_eq!(detect(text)[0].ripper, Ripper::EAC);
+
+    let err = detect_best(text).err().unwrap();
+    assert_eq!(err.message, "Unsupported file.");
+}
+
+#[test]
+fn min_confidence_picks_dominant_ripper() {
+    // A stray XLD CRC line doesn't outweigh a full EAC header
+    let text = format!("{}\n     CRC32 hash               : 3F2A1B4C\n", EAC_HEADER);
+    let detection = detect_best(&text).ok().unwrap();
+
+    assert_eq!(detection.ripper, Ripper::EAC);
+    assert!(detection.confidence >= 0.6);
+}
+
+#[test]
+fn ambiguous_eac_xld_names_candidates() {
+    let text = format!("{}\n{}", EAC_HEADER, XLD_HEADER);
+
+    let detections = detect(&text);
+    assert_eq!(detections.len(), 2);
+    assert_eq!(detections[0].score, detections[1].score);
+
+    let err = detect_best(&text).err().unwrap();
+    assert!(err.message.starts_with("Ambiguous file"));
+    assert!(err.message.contains("Exact Audio Copy"));
+    assert!(err.message.contains("X Lossless Decoder"));
+}
+
+// Uploads often lose the header lines or come translated, the checksum and CRC lines still give EAC away
+#[test]
+fn eac_without_english_header() {
+    let translated = format!(
+        "Exact Audio Copy V1.0 beta 3 от 29. августа 2011\n\nОтчёт EAC об извлечении, выполненном 14. марта 2024 г., 20:11\n\n{}",
+        EAC_BODY.replace("Test CRC", "CRC теста").replace("Copy CRC", "CRC копии"),
+    );
+
+    for text in [EAC_BODY, translated.as_str()] {
+        let detection = detect_best(text).ok().unwrap();
+        assert_eq!(detection.ripper, Ripper::EAC);
+        assert_eq!(detection.confidence, 1.0);
+        assert_eq!(split_logs(text).len(), 1);
+    }
+}
+
+#[test]
+fn crlf_checksum_counts() {
+    let text = EAC_BODY.replace('\n', "\r\n");
+
+    // Checksum and CRC markers
+    assert_eq!(detect(&text)[0].score, 11);
+    assert_eq!(detect_best(&text).ok().unwrap().ripper, Ripper::EAC);
+}
+
+#[test]
+fn eac_then_xld_upload() {
+    let text = format!("{}\n{}\n{}\n    CRC32 hash               : 3F2A1B4C\n", EAC_HEADER, EAC_BODY, XLD_HEADER);
+
+    let logs = split_logs(&text);
+    assert_eq!(logs.len(), 2);
+    assert_eq!(detect_best(logs[0]).ok().unwrap().ripper, Ripper::EAC);
+    assert!(logs[0].trim_end().ends_with("===="));
+    assert_eq!(detect_best(logs[1]).ok().unwrap().ripper, Ripper::XLD);
+}
+
+#[test]
+fn lf_separators_are_dropped() {
+    let log = format!("{}\n{}", EAC_HEADER, EAC_BODY);
+    let text = format!("{}\n{}\n\n{}", log, "-".repeat(60), log);
+
+    let logs = split_logs(&text);
+    assert_eq!(logs.len(), 2);
+    assert!(logs.iter().all(|segment| segment.trim() == log.trim()));
+}
+
+#[cfg(feature = "rip")]
+#[test]
+fn leading_junk_is_dropped() {
+    let text = format!("\n\n[quote]My rip of Geogaddi, thoughts?[/quote]\n\n{}", fixture_text("logs/rip/accurate.log"));
+
+    let detection = detect_best(&text).ok().unwrap();
+    assert_eq!(detection.ripper, Ripper::Rip);
+    assert!(text[detection.start..].starts_with("Rip 2.3"));
+
+    // The junk stays attached to the log instead of becoming a log of its own
+    assert_eq!(split_logs(&text).len(), 1);
+    let logs = parse_bytes("rip log with junk", text.as_bytes());
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].tracks.len(), 3);
+}
+
+#[cfg(feature = "cyanrip")]
+#[test]
+fn bom_before_header() {
+    // A UTF-8 BOM read as Windows-1252
+    let text = format!("\u{00EF}\u{00BB}\u{00BF}{}", fixture_text("logs/cyanrip/accurate.log"));
+
+    assert_eq!(detect_best(&text).ok().unwrap().ripper, Ripper::CyanRip);
+    assert_eq!(split_logs(&text).len(), 1);
+
+    let mut bytes = vec![0xEF, 0xBB, 0xBF];
+    bytes.extend(read_fixture("logs/cyanrip/accurate.log"));
+    let logs = parse_bytes("cyanrip log with BOM", &bytes);
+    assert_eq!(logs.len(), 1);
+    assert_eq!(logs[0].ripper, Ripper::CyanRip);
+}
+
+#[cfg(feature = "morituri")]
+#[test]
+fn line_endings_do_not_matter() {
+    let lf = fixture_text("logs/morituri/accurate.log");
+    assert!(!lf.contains('\r'));
+    let crlf = lf.replace('\n', "\r\n");
+
+    for text in [&lf, &crlf] {
+        let logs = parse_bytes("morituri log", text.as_bytes());
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].ripper, Ripper::Morituri);
+        assert_eq!(logs[0].toc.raw.entries.len(), 3);
+        assert_eq!(logs[0].tracks.len(), 3);
+    }
+}
@@ -0,0 +1,205 @@
+mod common;
+
+use cambia_core::evaluate::{EvaluationUnitClass, Evaluator};
+
+use common::{parse_bytes, parse_fixture, read_fixture};
+
+static FIXTURES: [&str; 8] = [
+    "logs/cyanrip/accurate.log",
+    "logs/dbpa/accurate.log",
+    "logs/ezcd/two_discs.log",
+    "logs/freac/two_conversions.log",
+    "logs/morituri/accurate.log",
+    "logs/rip/accurate.log",
+    "logs/whipper/errors.log",
+    "logs/xld/accurate.log",
+];
+
+#[cfg(all(feature = "freac", feature = "cambia_ev"))]
+#[test]
+fn cambia_grades_freac_settings() {
+    use cambia_core::evaluate::{cambia_evaluate::CambiaEvaluator, Grade, Score};
+
+    let log = &parse_fixture("logs/freac/two_conversions.log")[0];
+    let evaluation = CambiaEvaluator::new().evaluate(log);
+
+    let class = |message: &str| evaluation.evaluation_units.iter()
+        .find(|unit| unit.data.message.starts_with(message))
+        .map(|unit| unit.data.class.clone());
+
+    // Full paranoia with jitter correction passes, fre:ac never runs a test pass
+    assert!(class("Rip mode should be secure/paranoid") == Some(EvaluationUnitClass::Good));
+    assert!(class("Accurate stream should be utilised") == Some(EvaluationUnitClass::Good));
+    assert!(class("Test and copy was not used") == Some(EvaluationUnitClass::Neutral));
+
+    // fre:ac can't run a test pass or log its gap handling, neither makes a clean rip bad
+    assert!(evaluation.score == Score::Grade(Grade::NotIdeal));
+    assert!(evaluation.evaluation_units.iter().all(|unit| unit.unit_score != Score::Grade(Grade::Bad)));
+}
+
+// Dates pick the ruleset in effect at the time, the older Cambia one still graded unverifiable settings as bad
+#[cfg(all(feature = "freac", feature = "cambia_ev"))]
+#[test]
+fn cambia_ruleset_by_date() {
+    use cambia_core::{evaluate::{registry::select, Grade, Score}, parser::ParsedLogCombined};
+
+    // The second conversion ripped nothing and is bad under either ruleset
+    let mut parsed_logs = parse_fixture("logs/freac/two_conversions.log");
+    parsed_logs.truncate(1);
+    let combined = ParsedLogCombined { parsed_logs, encoding: String::from("UTF-8") };
+
+    for (name, ruleset, grade) in [("cambia", "2026-10-18", Grade::NotIdeal), ("cambia@2025-06-01", "2024-01-01", Grade::Bad)] {
+        let mut evaluators = select(&[String::from(name)]).ok().unwrap();
+        let evaluation = evaluators[0].evaluate_combined(&combined);
+        assert_eq!(evaluation.ruleset, ruleset);
+        assert!(evaluation.combined_score == Score::Grade(grade), "{}", name);
+    }
+
+    assert!(select(&[String::from("cambia@2023-12-31")]).is_err());
+}
+
+// RED reuses every OPS deduction, whipper is where the two part ways
+#[cfg(all(feature = "whipper", feature = "red_ev"))]
+#[test]
+fn red_rejects_whipper() {
+    use cambia_core::evaluate::{gazelle_evaluate::{ops_evaluate::OpsEvaluator, red_evaluate::RedEvaluator}, Score};
+
+    let log = &parse_fixture("logs/whipper/errors.log")[0];
+
+    let red = RedEvaluator::new().evaluate(log);
+    assert_eq!(red.score, Score::Numeric(0));
+    assert_eq!(red.evaluation_units.len(), 1);
+    assert!(red.evaluation_units[0].data.message.contains("could not determine ripper"));
+
+    let ops = OpsEvaluator::new().evaluate(log);
+    assert!(ops.evaluation_units.iter().all(|unit| !unit.data.message.contains("could not determine ripper")));
+    assert!(ops.score != Score::Numeric(0));
+}
+
+// Album gain is only expected when ReplayGain was on, which shows in the track gains
+#[cfg(all(feature = "xld", feature = "ops_ev"))]
+#[test]
+fn ops_album_gain_only_when_expected() {
+    use cambia_core::evaluate::gazelle_evaluate::ops_evaluate::OpsEvaluator;
+
+    let text = String::from_utf8(read_fixture("logs/xld/accurate.log")).unwrap();
+    let no_album_gain = text.replace("    Album gain               : -6.54 dB\n", "");
+    let no_gain = no_album_gain.lines().filter(|line| !line.contains("Track gain")).collect::<Vec<&str>>().join("\n");
+
+    for (log, flagged) in [(text.as_str(), false), (no_album_gain.as_str(), true), (no_gain.as_str(), false)] {
+        let parsed_log = &parse_bytes("XLD log", log.as_bytes())[0];
+        let evaluation = OpsEvaluator::new().evaluate(parsed_log);
+        let units: Vec<&str> = evaluation.evaluation_units.iter().map(|unit| unit.data.message.as_str()).collect();
+        assert_eq!(units.contains(&"Could not verify album gain"), flagged, "{:?}", units);
+    }
+}
+
+// The bundled YAML rules mirror the hand-written OPS evaluator, both have to agree on every fixture
+#[cfg(all(feature = "ops_ev", feature = "rules_ev"))]
+#[test]
+fn ops_rules_match_ops_evaluator() {
+    use std::collections::HashMap;
+
+    use cambia_core::{evaluate::{gazelle_evaluate::ops_evaluate::OpsEvaluator, rule_evaluate::RuleEvaluator, EvaluationUnit}, parser::ParsedLogCombined};
+
+    fn counted(units: &[EvaluationUnit]) -> HashMap<&EvaluationUnit, usize> {
+        let mut counts: HashMap<&EvaluationUnit, usize> = HashMap::new();
+        for unit in units {
+            *counts.entry(unit).or_default() += 1;
+        }
+        counts
+    }
+
+    for fixture in FIXTURES {
+        let parsed_logs = parse_fixture(fixture);
+
+        for parsed_log in parsed_logs.iter() {
+            let ops = OpsEvaluator::new().evaluate(parsed_log);
+            let rules = RuleEvaluator::ops().evaluate(parsed_log);
+
+            assert_eq!(ops.score, rules.score, "{}", fixture);
+            assert!(counted(&ops.evaluation_units) == counted(&rules.evaluation_units), "{}", fixture);
+        }
+
+        let combined = ParsedLogCombined { parsed_logs, encoding: String::from("UTF-8") };
+        assert_eq!(
+            OpsEvaluator::new().evaluate_combined(&combined).combined_score,
+            RuleEvaluator::ops().evaluate_combined(&combined).combined_score,
+            "{}", fixture,
+        );
+    }
+}
+
+// Settings the fixtures don't cover, forced onto a parsed log so that every release rule gets exercised
+#[cfg(all(feature = "whipper", feature = "ops_ev", feature = "rules_ev"))]
+#[test]
+fn ops_rules_match_ops_evaluator_on_settings() {
+    use cambia_core::{evaluate::{gazelle_evaluate::ops_evaluate::OpsEvaluator, rule_evaluate::RuleEvaluator}, extract::{Gap, Quartet, ReadMode, Ripper}};
+
+    // Not every setting type is Clone, so the values are rebuilt for each log
+    let read_modes = || [ReadMode::Secure, ReadMode::Paranoid, ReadMode::Fast, ReadMode::Burst, ReadMode::Unknown];
+    let quartets = || [Quartet::True, Quartet::False, Quartet::Unknown];
+    let gaps = || [Gap::Append, Gap::Prepend, Gap::Unknown];
+
+    for ripper in [Ripper::EAC, Ripper::XLD, Ripper::Whipper] {
+        for read_mode in 0..read_modes().len() {
+            for quartet in 0..quartets().len() {
+                for gap in 0..gaps().len() {
+                    let mut parsed_log = parse_fixture("logs/whipper/errors.log").remove(0);
+                    parsed_log.ripper = ripper;
+                    parsed_log.ripper_version = String::from("0.99");
+                    parsed_log.read_mode = read_modes().into_iter().nth(read_mode).unwrap();
+                    for setting in [
+                        &mut parsed_log.accurate_stream,
+                        &mut parsed_log.defeat_audio_cache,
+                        &mut parsed_log.use_c2,
+                        &mut parsed_log.test_and_copy,
+                        &mut parsed_log.fill_silence,
+                        &mut parsed_log.delete_silence,
+                        &mut parsed_log.use_null_samples,
+                        &mut parsed_log.normalize,
+                        &mut parsed_log.id3_enabled,
+                    ] {
+                        *setting = quartets().into_iter().nth(quartet).unwrap();
+                    }
+                    parsed_log.gap_handling = gaps().into_iter().nth(gap).unwrap();
+
+                    let ops = OpsEvaluator::new().evaluate(&parsed_log);
+                    let rules = RuleEvaluator::ops().evaluate(&parsed_log);
+                    let context = format!("{:?} {} {} {}", ripper, read_mode, quartet, gap);
+
+                    assert_eq!(ops.score, rules.score, "{}", context);
+                    assert_eq!(ops.evaluation_units.len(), rules.evaluation_units.len(), "{}", context);
+                    assert!(ops.evaluation_units.iter().all(|unit| rules.evaluation_units.contains(unit)), "{}", context);
+                }
+            }
+        }
+    }
+}
+
+// An old whipper fails outright, upgrading it would bring back every deduction the fail skipped
+#[cfg(all(feature = "whipper", feature = "ops_ev"))]
+#[test]
+fn fixed_score_skips_failed_evaluations() {
+    use cambia_core::{evaluate::{gazelle_evaluate::ops_evaluate::OpsEvaluator, Score}, parser::ParsedLogCombined, remediation::remediate};
+
+    let text = String::from_utf8(read_fixture("logs/whipper/errors.log")).unwrap();
+    let old = text.replace("whipper 0.10.0", "whipper 0.5.1");
+
+    for (bytes, failed) in [(text.as_bytes(), false), (old.as_bytes(), true)] {
+        let combined = ParsedLogCombined { parsed_logs: parse_bytes("whipper log", bytes), encoding: String::from("UTF-8") };
+        let mut evaluations = vec![OpsEvaluator::new().evaluate_combined(&combined)];
+        remediate(&combined, &mut evaluations);
+
+        let evaluation = &evaluations[0].evaluations[0];
+        assert_eq!(evaluation.failed, failed);
+        match failed {
+            true => {
+                assert_eq!(evaluation.score, Score::Numeric(0));
+                assert!(evaluation.remediations[0].is_some());
+                assert!(evaluation.fixed_score.is_none());
+            },
+            false => assert!(evaluation.fixed_score.is_some_and(|fixed| fixed.numeric() >= evaluation.score.numeric())),
+        }
+    }
+}
@@ -0,0 +1,289 @@
+mod common;
+
+use cambia_core::{anomaly::AnomalyKind, ctdb::CtdbStatus, extract::{Gap, Quartet, Ripper}, integrity::Integrity, track::AccurateRipStatus};
+
+use common::{parse_bytes, parse_fixture, parse_single, read_fixture};
+
+#[cfg(feature = "dbpa")]
+#[test]
+fn dbpa_accurate() {
+    let log = parse_single("logs/dbpa/accurate.log");
+
+    assert_eq!(log.ripper, Ripper::DBPA);
+    assert_eq!(log.ripper_version, "17.3");
+    assert_eq!(log.drive, "HL-DT-ST BD-RE WH16NS40");
+    assert_eq!(log.read_offset, Some(6));
+    assert_eq!(log.audio_encoder, vec!["flac"]);
+
+    let toc = &log.toc.raw;
+    assert_eq!(toc.entries.len(), 3);
+    assert_eq!(toc.entries[1].start_sector, 17620);
+    assert_eq!(toc.entries[2].end_sector, 53101);
+    assert_eq!(toc.lead_out, 53102);
+
+    assert_eq!(log.tracks.len(), 3);
+    assert_eq!(log.tracks[0].num, 1);
+    assert_eq!(log.tracks[0].filenames, vec![r"D:\Music\Boards of Canada\Geogaddi\01 Ready Lets Go.flac"]);
+    assert_eq!(log.tracks[0].test_and_copy.copy_hash, "3F2A1B4C");
+    assert_eq!(log.tracks[2].test_and_copy.copy_hash, "55AA66BB");
+
+    let ar = &log.tracks[0].ar_info[0];
+    assert!(ar.status == AccurateRipStatus::Match);
+    assert_eq!(ar.version, Some(2));
+    assert_eq!(ar.sign, "7AE8C1D0");
+    assert!(log.tracks[2].ar_info[0].status == AccurateRipStatus::Mismatch);
+    assert_eq!(log.tracks[2].errors.read.count, 12);
+}
+
+#[cfg(feature = "cyanrip")]
+#[test]
+fn cyanrip_accurate() {
+    let log = parse_single("logs/cyanrip/accurate.log");
+
+    assert_eq!(log.ripper, Ripper::CyanRip);
+    assert_eq!(log.ripper_version, "0.9.3.1");
+    assert_eq!(log.drive, "PLEXTOR DVDR PX-716A");
+    assert_eq!(log.read_offset, Some(30));
+    assert_eq!(log.release_info.artist, "Boards of Canada");
+    assert_eq!(log.release_info.title, "Geogaddi");
+    assert_eq!(log.audio_encoder, vec!["flac"]);
+
+    let toc = &log.toc.raw;
+    assert_eq!(toc.entries.len(), 3);
+    assert_eq!(toc.entries[1].start_sector, 17620);
+    assert_eq!(toc.lead_out, 53102);
+
+    assert_eq!(log.tracks.len(), 3);
+    assert_eq!(log.tracks[1].num, 2);
+    assert_eq!(log.tracks[1].filenames, vec!["Boards of Canada - Geogaddi/02 - Music Is Math.flac"]);
+    assert!(log.tracks[1].pregap_length.is_some());
+    assert!(log.tracks[0].pregap_length.is_none());
+    assert_eq!(log.tracks[0].test_and_copy.copy_hash, "3F2A1B4C");
+    assert_eq!(log.tracks[2].test_and_copy.copy_hash, "55AA66BB");
+
+    let ar = &log.tracks[0].ar_info;
+    assert_eq!(ar.len(), 2);
+    assert_eq!(ar[1].version, Some(2));
+    assert_eq!(ar[1].sign, "7AE8C1D0");
+    assert!(ar[1].status == AccurateRipStatus::Match);
+    assert!(log.tracks[2].ar_info.iter().all(|ar| ar.status == AccurateRipStatus::Mismatch));
+    assert_eq!(log.tracks[2].errors.read.count, 2);
+    assert!(log.overread == Quartet::False);
+    assert_eq!(log.tracks[0].preemphasis, Some(false));
+
+    // Settings missing from the log stay unknown
+    let text = String::from_utf8(read_fixture("logs/cyanrip/accurate.log")).unwrap();
+    let edited = text.replace("Overread:       0 frames\n", "").replacen("Preemphasis:   none", "Preemphasis:   present", 1).replacen("Preemphasis:   none", "Preemphasis:   garbled", 1);
+    let log = &parse_bytes("edited cyanrip log", edited.as_bytes())[0];
+    assert!(log.overread == Quartet::Unknown);
+    assert_eq!(log.tracks[0].preemphasis, Some(true));
+    assert_eq!(log.tracks[1].preemphasis, None);
+}
+
+#[cfg(feature = "ezcd")]
+#[test]
+fn ezcd_two_discs() {
+    let logs = parse_fixture("logs/ezcd/two_discs.log");
+    assert_eq!(logs.len(), 2);
+
+    let log = &logs[0];
+    assert_eq!(log.ripper, Ripper::EZCD);
+    assert_eq!(log.ripper_version, "9.5.2");
+    assert_eq!(log.release_info.title, "Geogaddi");
+    assert_eq!(log.drive, "HL-DT-ST BD-RE WH16NS40");
+    assert_eq!(log.read_offset, Some(6));
+    assert!(log.test_and_copy == Quartet::True);
+    assert!(log.gap_handling == Gap::Append);
+    assert_eq!(log.audio_encoder, vec!["flac"]);
+
+    let toc = &log.toc.raw;
+    assert_eq!(toc.entries.len(), 2);
+    assert_eq!(toc.entries[1].start_sector, 17620);
+    assert_eq!(toc.lead_out, 38912);
+
+    assert_eq!(log.tracks.len(), 2);
+    assert_eq!(log.tracks[1].num, 2);
+    assert_eq!(log.tracks[1].filenames, vec![r"D:\Music\Boards of Canada\Geogaddi\02.flac"]);
+    assert_eq!(log.tracks[0].test_and_copy.test_hash, "3F2A1B4C");
+    assert_eq!(log.tracks[0].test_and_copy.copy_hash, "3F2A1B4C");
+    assert!(log.tracks[0].ar_info[0].status == AccurateRipStatus::Match);
+    assert_eq!(log.tracks[0].ar_info[0].sign, "7AE8C1D0");
+    assert!(log.ctdb.as_ref().is_some_and(|ctdb| ctdb.status == CtdbStatus::Match));
+
+    let bonus = &logs[1];
+    assert_eq!(bonus.release_info.title, "Geogaddi Bonus");
+    assert_eq!(bonus.toc.raw.entries.len(), 1);
+    assert_eq!(bonus.tracks.len(), 1);
+    assert!(bonus.tracks[0].ar_info[0].status == AccurateRipStatus::NotFound);
+
+    // Out of range numbers from user input skip the line instead of panicking
+    let text = String::from_utf8(read_fixture("logs/ezcd/two_discs.log")).unwrap();
+    let edited = text.replacen("(AR v2)", "(AR v999)", 1).replacen("(confidence 51)", "(confidence 99999999999)", 1);
+    let logs = parse_bytes("edited EZCD log", edited.as_bytes());
+    assert!(logs[0].tracks.iter().flat_map(|track| track.ar_info.iter()).all(|ar| ar.sign != "7AE8C1D0" && ar.sign != "1B2C3D4E"));
+}
+
+#[cfg(feature = "morituri")]
+#[test]
+fn morituri_accurate() {
+    let log = parse_single("logs/morituri/accurate.log");
+
+    assert_eq!(log.ripper, Ripper::Morituri);
+    assert_eq!(log.ripper_version, "0.2.3");
+    assert_eq!(log.release_info.artist, "Boards of Canada");
+    assert_eq!(log.release_info.title, "Geogaddi");
+    assert_eq!(log.drive, "PLEXTOR DVDR PX-716A");
+    assert_eq!(log.read_offset, Some(30));
+
+    let toc = &log.toc.raw;
+    assert_eq!(toc.entries.len(), 3);
+    assert_eq!(toc.entries[1].start_sector, 17620);
+    assert_eq!(toc.entries[1].end_sector, 38911);
+    assert_eq!(toc.lead_out, 53102);
+
+    assert_eq!(log.tracks.len(), 3);
+    assert_eq!(log.tracks[0].num, 1);
+    assert_eq!(log.tracks[0].filenames, vec!["/home/user/Music/Boards of Canada - Geogaddi/01. Boards of Canada - Ready Lets Go.flac"]);
+    assert!(log.tracks[0].pregap_length.is_none());
+    assert!(log.tracks[1].pregap_length.is_some());
+    assert_eq!(log.tracks[0].peak_level, Some(0.983));
+    assert_eq!(log.tracks[1].test_and_copy.test_hash, "90C1D2E3");
+    assert_eq!(log.tracks[1].test_and_copy.copy_hash, "90C1D2E3");
+    assert!(log.tracks[2].test_and_copy.integrity == Integrity::Mismatch);
+
+    let ar = &log.tracks[0].ar_info[0];
+    assert_eq!(ar.version, Some(1));
+    assert_eq!(ar.sign, "1D3A55F0");
+    assert!(ar.status == AccurateRipStatus::Match);
+    assert!(log.tracks[2].ar_info[0].status == AccurateRipStatus::Mismatch);
+}
+
+#[cfg(feature = "rip")]
+#[test]
+fn rip_accurate() {
+    let log = parse_single("logs/rip/accurate.log");
+
+    assert_eq!(log.ripper, Ripper::Rip);
+    assert_eq!(log.ripper_version, "2.3");
+    assert_eq!(log.release_info.artist, "Boards of Canada");
+    assert_eq!(log.release_info.title, "Geogaddi");
+    assert_eq!(log.drive, "PLEXTOR DVDR PX-716A");
+    assert_eq!(log.read_offset, Some(30));
+    assert_eq!(log.audio_encoder, vec!["m4a"]);
+    assert!(log.use_c2 == Quartet::False);
+
+    let toc = &log.toc.raw;
+    assert_eq!(toc.entries.len(), 3);
+    assert_eq!(toc.entries[1].start_sector, 17620);
+    assert_eq!(toc.entries[2].end_sector, 53101);
+    assert_eq!(toc.lead_out, 53102);
+
+    assert_eq!(log.tracks.len(), 3);
+    assert_eq!(log.tracks[0].filenames, vec!["/Users/user/Music/Boards of Canada/Geogaddi/01 Ready Lets Go.m4a"]);
+    assert!(log.tracks[1].pregap_length.is_some());
+    assert_eq!(log.tracks[1].test_and_copy.copy_hash, "90C1D2E3");
+    assert!(log.tracks[2].test_and_copy.integrity == Integrity::Mismatch);
+    assert_eq!(log.tracks[2].errors.read.count, 4);
+    assert_eq!(log.tracks[2].errors.jitter_generic.count, 37);
+
+    let ar = &log.tracks[0].ar_info;
+    assert_eq!(ar.len(), 2);
+    assert_eq!(ar[1].version, Some(2));
+    assert_eq!(ar[1].sign, "7AE8C1D0");
+    assert!(ar[1].status == AccurateRipStatus::Match);
+    assert!(log.tracks[2].ar_info.iter().all(|ar| ar.status == AccurateRipStatus::Mismatch));
+}
+
+#[cfg(feature = "freac")]
+#[test]
+fn freac_flags_file_conversions() {
+    let logs = parse_fixture("logs/freac/two_conversions.log");
+    assert_eq!(logs.len(), 2);
+
+    let log = &logs[0];
+    assert_eq!(log.ripper, Ripper::FreAc);
+    assert_eq!(log.ripper_version, "1.1.7");
+    assert_eq!(log.drive, "PLEXTOR DVDR PX-716A");
+    assert_eq!(log.read_offset, Some(30));
+    assert_eq!(log.audio_encoder, vec!["flac"]);
+    assert!(log.accurate_stream == Quartet::True);
+
+    let toc = &log.toc.raw;
+    assert_eq!(toc.entries.len(), 3);
+    assert_eq!(toc.entries[1].start_sector, 17620);
+    assert_eq!(toc.lead_out, 53102);
+
+    assert_eq!(log.tracks.len(), 3);
+    assert_eq!(log.tracks[1].filenames, vec![r"C:\Users\user\Music\Boards of Canada - Geogaddi\02 - Music Is Math.flac"]);
+    assert_eq!(log.tracks[0].test_and_copy.copy_hash, "3F2A1B4C");
+    assert_eq!(log.tracks[2].errors.read.count, 3);
+    assert_eq!(log.tracks[2].errors.jitter_generic.count, 21);
+
+    let ar = &log.tracks[0].ar_info;
+    assert_eq!(ar.len(), 2);
+    assert_eq!(ar[0].version, Some(1));
+    assert_eq!(ar[0].sign, "1D3A55F0");
+    assert!(ar[0].status == AccurateRipStatus::Match);
+    assert!(log.tracks[2].ar_info.iter().all(|ar| ar.status == AccurateRipStatus::Mismatch));
+    assert!(log.anomalies.is_empty());
+
+    // The second conversion only re-encoded files, it's kept and flagged
+    let conversion = &logs[1];
+    assert_eq!(conversion.ripper, Ripper::FreAc);
+    assert!(conversion.tracks.is_empty());
+    let kinds: Vec<AnomalyKind> = conversion.anomalies.iter().map(|anomaly| anomaly.kind).collect();
+    assert_eq!(kinds, vec![AnomalyKind::NoTracks]);
+}
+
+#[cfg(feature = "whipper")]
+#[test]
+fn whipper_status_report() {
+    let log = parse_single("logs/whipper/errors.log");
+
+    assert_eq!(log.ripper, Ripper::Whipper);
+    assert_eq!(log.ripper_version, "0.10.0");
+    assert_eq!(log.read_offset, Some(30));
+    assert_eq!(log.toc.raw.entries.len(), 2);
+    assert_eq!(log.tracks.len(), 2);
+
+    let ar = &log.tracks[0].ar_info;
+    assert_eq!(ar.len(), 2);
+    assert_eq!(ar[1].sign, "7AE8C1D0");
+    assert!(ar[1].status == AccurateRipStatus::Match);
+    assert!(log.tracks[1].ar_info.iter().all(|ar| ar.status == AccurateRipStatus::Mismatch));
+    assert!(log.tracks[1].test_and_copy.integrity == Integrity::Mismatch);
+
+    let kinds: Vec<AnomalyKind> = log.anomalies.iter().map(|anomaly| anomaly.kind).collect();
+    assert_eq!(kinds, vec![AnomalyKind::RipperReportedErrors]);
+    assert!(log.anomalies[0].message.contains("Some tracks had CRC mismatches"));
+
+    // Cut right before the status report
+    let text = String::from_utf8(read_fixture("logs/whipper/errors.log")).unwrap();
+    let truncated = &text[..text.find("Conclusive status report:").unwrap()];
+    let log = &parse_bytes("truncated whipper log", truncated.as_bytes())[0];
+    let kinds: Vec<AnomalyKind> = log.anomalies.iter().map(|anomaly| anomaly.kind).collect();
+    assert_eq!(kinds, vec![AnomalyKind::Truncated]);
+}
+
+#[cfg(feature = "xld")]
+#[test]
+fn xld_settings_and_skip_zero() {
+    let log = parse_single("logs/xld/accurate.log");
+
+    assert_eq!(log.ripper, Ripper::XLD);
+    assert_eq!(log.max_retry_count, Some(20));
+    assert_eq!(log.album_gain, Some(-6.54));
+    assert_eq!(log.tracks.len(), 2);
+    assert_eq!(log.tracks[1].gain, Some(-7.03));
+
+    let tc = &log.tracks[0].test_and_copy;
+    assert_eq!(tc.test_skipzero_hash, "0A1B2C3D");
+    assert_eq!(tc.copy_skipzero_hash, "0A1B2C3D");
+    assert!(tc.integrity_skipzero == Integrity::Match);
+
+    let text = String::from_utf8(read_fixture("logs/xld/accurate.log")).unwrap();
+    let edited = text.replace("CRC32 hash (test run, skip zero) : 5E6F7A8B", "CRC32 hash (test run, skip zero) : 00000001");
+    let log = &parse_bytes("edited XLD log", edited.as_bytes())[0];
+    assert!(log.tracks[1].test_and_copy.integrity == Integrity::Match);
+    assert!(log.tracks[1].test_and_copy.integrity_skipzero == Integrity::Mismatch);
+}
@@ -0,0 +1,86 @@
+mod common;
+
+use cambia_core::{toc::{Toc, TocEntry, TocRaw, TrackType}, util::Time};
+
+use common::parse_single;
+
+fn entry(track: u32, start_sector: u32, end_sector: u32) -> TocEntry {
+    TocEntry::new(track, Time::from_ss("0"), Time::from_ss("0"), start_sector, end_sector)
+}
+
+// Expected IDs follow CUETools' CalculateAccurateRipId: audio tracks counted from 1, the end of the whole disc as lead-out
+#[test]
+fn enhanced_cd_ids() {
+    let toc = Toc::new(TocRaw::new(vec![
+        entry(1, 0, 17619),
+        entry(2, 17620, 38911),
+        // Data session after the 11400 sector gap
+        entry(3, 50312, 80000),
+    ]));
+
+    assert_eq!(toc.raw.sessions, 2);
+    assert_eq!(toc.raw.data_tracks, 1);
+    assert_eq!(toc.raw.lead_out, 38912);
+    assert_eq!(toc.raw.disc_lead_out, 80001);
+    assert!(toc.raw.entries[2].track_type == TrackType::Data);
+
+    assert_eq!(toc.accurip_tocid.hash, "00017d55-0004332c-1c042a03");
+    assert_eq!(toc.accurip_tocid.url, "http://www.accuraterip.com/accuraterip/5/5/d/dBAR-002-00017d55-0004332c-1c042a03.bin");
+    assert_eq!(toc.freedb.hash, "1C042A03");
+}
+
+#[test]
+fn mixed_mode_ids() {
+    let mut raw = TocRaw::new(vec![
+        entry(1, 0, 19999),
+        // The data to audio transition is left out of the data track
+        entry(2, 20150, 37769),
+        entry(3, 37770, 59061),
+    ]);
+    assert_eq!(raw.sessions, 1);
+    assert_eq!(raw.data_tracks, 0);
+
+    // Only tracks 2 and 3 were ripped
+    assert!(raw.mark_leading_data(&[2, 3]));
+    assert!(!raw.mark_leading_data(&[2, 3]));
+    let toc = Toc::new(raw);
+
+    assert_eq!(toc.raw.data_tracks, 1);
+    assert_eq!(toc.raw.audio_entries().len(), 2);
+    assert_eq!(toc.accurip_tocid.hash, "0001c8f6-000429ec-15031303");
+    assert_eq!(toc.accurip_tocid.url, "http://www.accuraterip.com/accuraterip/6/f/8/dBAR-002-0001c8f6-000429ec-15031303.bin");
+}
+
+#[test]
+fn partial_rips_keep_audio() {
+    let mut raw = TocRaw::new(vec![entry(1, 0, 19999), entry(2, 20000, 37769), entry(3, 37770, 59061)]);
+
+    assert!(!raw.mark_leading_data(&[2]));
+    // An audio-only disc ripped from track 2 onward, nothing separates track 1 from the rest
+    assert!(!raw.mark_leading_data(&[2, 3]));
+    assert!(!raw.mark_leading_data(&[1, 2, 3]));
+    assert!(!raw.mark_leading_data(&[]));
+    assert_eq!(raw.data_tracks, 0);
+}
+
+#[test]
+fn short_gaps_stay_in_the_session() {
+    let toc = Toc::new(TocRaw::new(vec![entry(1, 0, 17619), entry(2, 17720, 38911)]));
+
+    assert_eq!(toc.raw.entries.len(), 2);
+    assert_eq!(toc.raw.sessions, 1);
+    assert_eq!(toc.raw.lead_out, 38912);
+    assert!(!toc.accurip_tocid.hash.is_empty());
+    assert!(!toc.mbz.hash.is_empty());
+}
+
+#[cfg(feature = "whipper")]
+#[test]
+fn whipper_mixed_mode() {
+    let log = parse_single("logs/whipper/mixed_mode.log");
+
+    assert_eq!(log.toc.raw.entries.len(), 3);
+    assert!(log.toc.raw.entries[0].track_type == TrackType::Data);
+    assert_eq!(log.toc.raw.data_tracks, 1);
+    assert_eq!(log.toc.accurip_tocid.hash, "0001c8f6-000429ec-15031303");
+}
@@ -32,6 +32,12 @@ pub struct Args {
     /// Save the uploaded logs to a directory
     #[arg(long, env = "CAMBIA_SAVE_LOGS")]
     pub save_logs: Option<PathBuf>,
+    /// Comma separated evaluators to run, uses the default set if empty (e.g. ops,red or ops_rules@2023-01-01)
+    #[arg(long, env = "CAMBIA_EVALUATORS", value_delimiter = ',')]
+    pub evaluators: Vec<String>,
+    /// Print a CUE sheet rebuilt from the log at --path instead of the evaluation (auto, single, appended, prepended)
+    #[arg(long, num_args = 0..=1, default_missing_value = "auto")]
+    pub cue: Option<String>,
 }
 
 #[tokio::main]
@@ -44,6 +50,11 @@ pub async fn main() {
         tracing::info!("Log saving is enabled");
     }
 
+    if let Err(e) = cambia_core::evaluate::registry::select(&args.evaluators) {
+        tracing::error!("{}", e);
+        return;
+    }
+
     run(args).await;
 }
 
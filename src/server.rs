@@ -13,7 +13,9 @@ use futures::{sink::SinkExt, stream::StreamExt};
 use tower_http::trace::{DefaultMakeSpan, TraceLayer};
 use axum_client_ip::{InsecureClientIp, SecureClientIp, SecureClientIpSource};
 use cambia_core::error::CambiaError;
-use cambia_core::handler::{parse_log_bytes, translate_log_bytes};
+use cambia_core::evaluate::registry;
+use cambia_core::cue::write::parse_layout;
+use cambia_core::handler::{cue_from_log_bytes, parse_log_bytes, translate_log_bytes};
 use cambia_core::response::CambiaResponse;
 use crate::Args;
 use crate::util::save_rip_log;
@@ -66,6 +68,26 @@ where
     }
 }
 
+#[derive(Deserialize)]
+struct EvaluatorQuery {
+    evaluators: Option<String>,
+}
+
+impl EvaluatorQuery {
+    // Falls back to the evaluators picked on startup
+    fn names(&self, args: &Args) -> Vec<String> {
+        match &self.evaluators {
+            Some(evaluators) => registry::parse_names(evaluators),
+            None => args.evaluators.clone(),
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct CueQuery {
+    layout: Option<String>,
+}
+
 // TODO: Check for security implications
 pub struct CambiaServer {
     args: Args
@@ -82,6 +104,8 @@ impl CambiaServer {
         let single_upload = Router::new()
             .route("/v1/upload", post(Self::upload_log))
             .route("/v1/translate", post(Self::translate_log))
+            .route("/v1/cue", post(Self::rebuild_cue))
+            .route("/v1/evaluators", get(Self::list_evaluators))
             .layer(CorsLayer::permissive())
             .layer(CompressionLayer::new().gzip(true).no_br().no_zstd());
 
@@ -152,13 +176,20 @@ impl CambiaServer {
 
     async fn ws_handler(
         Extension(args): Extension<Args>,
+        Query(query): Query<EvaluatorQuery>,
         ws: WebSocketUpgrade,
         ConnectInfo(addr): ConnectInfo<SocketAddr>,
     ) -> impl IntoResponse {
-        ws.on_upgrade(move |socket| Self::handle_socket(args, socket, addr))
+        // The selection holds for the whole connection, reject it upfront
+        let evaluators = query.names(&args);
+        if let Err(e) = registry::select(&evaluators) {
+            return (StatusCode::BAD_REQUEST, e.to_string()).into_response();
+        }
+
+        ws.on_upgrade(move |socket| Self::handle_socket(args, evaluators, socket, addr))
     }
 
-    async fn handle_socket(args: Args, socket: WebSocket, who: SocketAddr) {
+    async fn handle_socket(args: Args, evaluators: Vec<String>, socket: WebSocket, who: SocketAddr) {
         let (mut sender, mut receiver) = socket.split();
 
         // TODO: There should be a better way to do this
@@ -166,7 +197,7 @@ impl CambiaServer {
             let mut cnt = 0;
             while let Some(Ok(msg)) = receiver.next().await {
                 cnt += 1;
-                let processed = Self::process_message(&args, msg, who);
+                let processed = Self::process_message(&args, &evaluators, msg, who);
                 if processed.is_break() {
                     break;
                 } else if let ControlFlow::Continue(val) = processed {
@@ -190,10 +221,10 @@ impl CambiaServer {
         tracing::trace!("Websocket context {} destroyed", who);
     }
 
-    fn process_message(args: &Args, msg: Message, who: SocketAddr) -> ControlFlow<(), Vec<u8>> {
+    fn process_message(args: &Args, evaluators: &[String], msg: Message, who: SocketAddr) -> ControlFlow<(), Vec<u8>> {
         match msg {
             Message::Binary(d) => {
-                let enc: Vec<u8> = match Self::parse_ws_request(args, d) {
+                let enc: Vec<u8> = match Self::parse_ws_request(args, evaluators, d) {
                     Ok(res) => rmp_serde::encode::to_vec_named(&res).unwrap(),
                     Err(e) => rmp_serde::encode::to_vec_named(&e).unwrap(),
                 };
@@ -215,14 +246,14 @@ impl CambiaServer {
         ControlFlow::Continue(Vec::new())
     }
 
-    fn parse_ws_request(args: &Args, mut ws_body: Vec<u8>) -> Result<CambiaResponse, CambiaError> {
+    fn parse_ws_request(args: &Args, evaluators: &[String], mut ws_body: Vec<u8>) -> Result<CambiaResponse, CambiaError> {
         // xxH64 is 8 bytes
         if ws_body.len() < 8 {
             return Err(CambiaError::new_anon("WS message length too small"));
         }
 
         let log_bytes = ws_body.split_off(8);
-        let res = parse_log_bytes(ws_body, &log_bytes);
+        let res = parse_log_bytes(ws_body, &log_bytes, evaluators);
 
         if let Some(save_logs) = args.save_logs.clone() {
             if let Ok(ref res) = res {
@@ -245,9 +276,9 @@ impl CambiaServer {
             .unwrap();
     }
 
-    async fn upload_log(fmt: Format, bytes: Bytes) -> impl IntoResponse {
+    async fn upload_log(Extension(args): Extension<Args>, fmt: Format, Query(query): Query<EvaluatorQuery>, bytes: Bytes) -> impl IntoResponse {
         let bytes_vec = bytes.to_vec();
-        match parse_log_bytes(Vec::new(), &bytes_vec) {
+        match parse_log_bytes(Vec::new(), &bytes_vec, &query.names(&args)) {
             Ok(parsed) => {
                 tracing::debug!("{}", serde_json::to_string(&parsed).unwrap());
                 (StatusCode::OK, fmt.render(parsed))
@@ -256,6 +287,10 @@ impl CambiaServer {
         }
     }
 
+    async fn list_evaluators(fmt: Format) -> impl IntoResponse {
+        fmt.render(registry::available())
+    }
+
     async fn translate_log(bytes: Bytes) -> impl IntoResponse {
         let bytes_vec = bytes.to_vec();
 
@@ -264,6 +299,18 @@ impl CambiaServer {
             Err(e) => (StatusCode::BAD_REQUEST, e.to_string().into_response()),
         }
     }
+
+    async fn rebuild_cue(Query(query): Query<CueQuery>, bytes: Bytes) -> impl IntoResponse {
+        let layout = match parse_layout(query.layout.as_deref().unwrap_or_default()) {
+            Ok(layout) => layout,
+            Err(e) => return (StatusCode::BAD_REQUEST, e.to_string().into_response()),
+        };
+
+        match cue_from_log_bytes(bytes.to_vec(), layout) {
+            Ok(cue) => (StatusCode::OK, cue.into_response()),
+            Err(e) => (StatusCode::BAD_REQUEST, e.to_string().into_response()),
+        }
+    }
 }
 
 pub fn port_in_range(s: &str) -> Result<String, String> {
@@ -1,7 +1,8 @@
 use std::fs::OpenOptions;
 use std::io::Read;
 use std::path::PathBuf;
-use cambia_core::handler::parse_log_bytes;
+use cambia_core::cue::write::parse_layout;
+use cambia_core::handler::{cue_from_log_bytes, parse_log_bytes};
 use crate::Args;
 
 pub fn parse_file(filepath: &str, args: Args) {
@@ -15,14 +16,20 @@ pub fn parse_file(filepath: &str, args: Args) {
 		"Could not read file"
 	);
 
-	let parsed = match parse_log_bytes(Vec::new(), &raw) {
+	if let Some(layout) = &args.cue {
+		match parse_layout(layout).and_then(|layout| cue_from_log_bytes(raw, layout)) {
+			Ok(cue) => print!("{}", cue),
+			Err(e) => tracing::error!("{}", e),
+		}
+		return;
+	}
+
+	let parsed = match parse_log_bytes(Vec::new(), &raw, &args.evaluators) {
 		Ok(parsed) => parsed,
 		Err(_) => return,
 	};
 
-	if let Ok(parsed) = parse_log_bytes(Vec::new(), &raw) {
-		println!("{}", serde_json::to_string(&parsed).unwrap());
-	}
+	println!("{}", serde_json::to_string(&parsed).unwrap());
 
 	if let Some(save_logs) = args.save_logs {
 		save_rip_log(save_logs, &parsed.id, &raw);
@@ -3,6 +3,7 @@ import type { ResponseEntry } from './types/ResponseEntry';
 import type { CambiaResponse } from './types/CambiaResponse';
 import type { CambiaError } from './types/CambiaError';
 import { getRipInfoMpMulti } from './api/CambiaApi';
+import { getNumericScore } from './utils';
 
 export const processedCount = writable(0);
 export const responseStore = writable(new Array<ResponseEntry>());
@@ -41,9 +42,13 @@ export function updateUnknown() {
 
 export function updateStat(content: CambiaResponse) {
 	// TODO: Score-based evaluation is dumb; switch to Cambia eval in future
-	const score = parseInt(
+	const score = getNumericScore(
 		content.evaluation_combined.filter((x) => x.evaluator === 'OPS')[0].combined_score
 	);
+	if (score === null) {
+		updateUnknown();
+		return;
+	}
 	switch (true) {
 		case score < 0:
 			badCount.update((c) => c + 1);
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AccurateRipStatus } from "./AccurateRipStatus";
+
+export interface AccurateRipTrackComparison { num: number, version: number | null, sign: string, matching: number, total: number, status: AccurateRipStatus, }
\ No newline at end of file
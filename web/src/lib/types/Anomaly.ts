@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { AnomalyKind } from "./AnomalyKind";
+
+export interface Anomaly { kind: AnomalyKind, track: number | null, message: string, }
\ No newline at end of file
@@ -0,0 +1,6 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CtdbConfidence } from "./CtdbConfidence";
+import type { CtdbStatus } from "./CtdbStatus";
+import type { CtdbTrack } from "./CtdbTrack";
+
+export interface Ctdb { tocid: string, crc: string, confidence: CtdbConfidence | null, differing_samples: number | null, repair_possible: boolean, status: CtdbStatus, tracks: Array<CtdbTrack>, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CtdbConfidence } from "./CtdbConfidence";
+import type { CtdbStatus } from "./CtdbStatus";
+
+export interface CtdbTrack { num: number, confidence: CtdbConfidence, differing_samples: number | null, status: CtdbStatus, }
\ No newline at end of file
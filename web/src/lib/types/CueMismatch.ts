@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CueMismatchKind } from "./CueMismatchKind";
+
+export interface CueMismatch { kind: CueMismatchKind, track: number | null, message: string, }
\ No newline at end of file
@@ -0,0 +1,5 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CueFlag } from "./CueFlag";
+import type { CueIndex } from "./CueIndex";
+
+export interface CueTrack { num: number, data_type: string, title: string | null, performer: string | null, isrc: string | null, flags: Array<CueFlag>, pregap: number | null, postgap: number | null, indices: Array<CueIndex>, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { CueIndexPosition } from "./CueIndexPosition";
+
+export interface CueTrackPosition { num: number, start_sector: number, pregap_length: string, indices: Array<CueIndexPosition>, }
\ No newline at end of file
@@ -1,4 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { EvaluationUnit } from "./EvaluationUnit";
+import type { Remediation } from "./Remediation";
+import type { Score } from "./Score";
 
-export interface Evaluation { score: string, evaluation_units: Array<EvaluationUnit>, }
\ No newline at end of file
+export interface Evaluation { score: Score, evaluation_units: Array<EvaluationUnit>, remediations: Array<Remediation | null>, fixed_score: Score | null, failed: boolean, }
\ No newline at end of file
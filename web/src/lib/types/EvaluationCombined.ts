@@ -1,5 +1,6 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
 import type { Evaluation } from "./Evaluation";
 import type { EvaluatorType } from "./EvaluatorType";
+import type { Score } from "./Score";
 
-export interface EvaluationCombined { evaluator: EvaluatorType, combined_score: string, evaluations: Array<Evaluation>, }
\ No newline at end of file
+export interface EvaluationCombined { evaluator: EvaluatorType, ruleset: string, combined_score: Score, evaluations: Array<Evaluation>, }
\ No newline at end of file
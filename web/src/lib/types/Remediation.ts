@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { RemediationAction } from "./RemediationAction";
+
+export interface Remediation { setting: string, location: string, value: string, action: RemediationAction, }
\ No newline at end of file
@@ -0,0 +1,4 @@
+// This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { Grade } from "./Grade";
+
+export type Score = { "Numeric": number } | { "Grade": Grade } | "NotApplicable";
\ No newline at end of file
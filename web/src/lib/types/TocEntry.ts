@@ -1,3 +1,4 @@
 // This file was generated by [ts-rs](https://github.com/Aleph-Alpha/ts-rs). Do not edit this file manually.
+import type { TrackType } from "./TrackType";
 
-export interface TocEntry { track: number, start: string, length: string, start_sector: number, end_sector: number, }
\ No newline at end of file
+export interface TocEntry { track: number, start: string, length: string, start_sector: number, end_sector: number, track_type: TrackType, session: number, }
\ No newline at end of file
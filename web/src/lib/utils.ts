@@ -6,6 +6,7 @@ import type { CambiaError } from './types/CambiaError';
 import { Packr } from 'msgpackr';
 import type { EvaluationUnitScope } from './types/EvaluationUnitScope';
 import type { Quartet } from './types/Quartet';
+import type { Score } from './types/Score';
 
 const packr = new Packr({ useRecords: false });
 
@@ -92,9 +93,30 @@ export function hexify(data: number[]): string {
 		.toLowerCase();
 }
 
-export function getScoreVariant(score: string): string {
-	if (isNumeric(score)) {
-		const numScore = parseFloat(score);
+export function getNumericScore(score: Score): number | null {
+	return typeof score === 'object' && 'Numeric' in score ? score.Numeric : null;
+}
+
+export function scoreStringify(score: Score): string {
+	if (typeof score === 'object') {
+		return 'Numeric' in score ? score.Numeric.toString() : score.Grade;
+	}
+	return 'N/A';
+}
+
+export function getScoreVariant(score: Score): string {
+	if (typeof score === 'object' && 'Grade' in score) {
+		switch (score.Grade) {
+			case 'Good':
+				return 'variant-soft-success';
+			case 'NotIdeal':
+				return 'variant-soft-warning';
+			case 'Bad':
+				return 'variant-soft-error';
+		}
+	}
+	const numScore = getNumericScore(score);
+	if (numScore !== null) {
 		switch (true) {
 			case numScore == 100:
 				return 'variant-soft-success';